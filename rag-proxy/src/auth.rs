@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::config::{hash_key, ApiKeyConfig, ApiKeyScope};
+use crate::AppState;
+
+/// Routes that mutate Memory Bank state or background worker behavior require an `admin` key;
+/// everything else only needs a valid key (any scope). `/health` and `/metrics` aren't scoped
+/// here at all - `require_api_key` skips them unconditionally so monitoring keeps working
+/// without a key.
+fn required_scope(path: &str) -> ApiKeyScope {
+    if path == "/api/learn" || path.starts_with("/api/workers/") {
+        ApiKeyScope::Admin
+    } else {
+        ApiKeyScope::Read
+    }
+}
+
+/// Checks `Authorization: Bearer <key>` against `config.auth.api_keys`. A no-op when no keys
+/// are configured, so dev/demo deployments keep working with no setup. `/health` and `/metrics`
+/// are always open so load balancers and scrapers don't need a credential. Missing or unknown
+/// keys get `401`; a recognized key without the required scope gets `403`.
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let path = request.uri().path();
+    if path == "/health" || path == "/metrics" || state.config.auth.api_keys.is_empty() {
+        return Ok(next.run(request).await);
+    }
+
+    let required = required_scope(path);
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let matched = match provided {
+        Some(key) => find_matching_key(&state.config.auth.api_keys, key),
+        None => None,
+    };
+
+    match matched {
+        None => Err(StatusCode::UNAUTHORIZED),
+        Some(configured) if configured.scope.satisfies(required) => Ok(next.run(request).await),
+        Some(_) => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+/// Hashes `presented` and compares it against every configured key in constant time, so a
+/// caller can't learn anything about which (if any) hash it's close to from response latency.
+/// Still short-circuits across keys once a match is found, same as any lookup would.
+fn find_matching_key<'a>(keys: &'a [ApiKeyConfig], presented: &str) -> Option<&'a ApiKeyConfig> {
+    let presented_hash = hash_key(presented);
+    keys.iter().find(|configured| constant_time_eq(configured.key_hash.as_bytes(), presented_hash.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
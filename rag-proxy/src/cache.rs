@@ -1,13 +1,56 @@
+use axum::http::StatusCode;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::cache_backend::CacheBackend;
+
+/// Number of random keys sampled per eviction round for `Lru`/`Lfu` (Redis-style approximate
+/// eviction: cheap enough to run under a lock-free `DashMap` without ever scanning the whole map).
+const SAMPLE_SIZE: usize = 16;
+/// Best candidates kept across sampling rounds so repeated rounds converge toward true LRU/LFU
+/// instead of just picking the worst of one random sample.
+const EVICTION_POOL_SIZE: usize = 8;
+
+/// Strategy `CacheManager` uses to pick victims once `max_size` is reached, beyond the
+/// always-on sweep of already-expired entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvictionPolicy {
+    /// Evict whichever entries are closest to expiry (the original behavior).
+    Ttl,
+    /// Approximate least-recently-used: sample random keys, evict the one with the oldest
+    /// `last_accessed`.
+    Lru,
+    /// Approximate least-frequently-used: sample random keys, evict the one with the lowest
+    /// `access_count`.
+    Lfu,
+}
+
+impl EvictionPolicy {
+    pub fn parse(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "lru" => EvictionPolicy::Lru,
+            "lfu" => EvictionPolicy::Lfu,
+            _ => EvictionPolicy::Ttl,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct CacheEntry<T> {
     pub value: T,
     pub expires_at: Instant,
     pub created_at: Instant,
+    /// Updated on every `get` hit; read by the `Lru` eviction policy.
+    pub last_accessed: Instant,
+    /// Incremented on every `get` hit; read by the `Lfu` eviction policy.
+    pub access_count: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,40 +65,44 @@ pub struct CacheStats {
 pub struct CacheManager {
     cache: Arc<DashMap<String, CacheEntry<String>>>,
     max_size: usize,
+    eviction_policy: EvictionPolicy,
     stats: Arc<dashmap::DashMap<String, u64>>,
 }
 
 impl CacheManager {
     pub fn new(max_size: usize) -> Self {
+        Self::with_eviction_policy(max_size, EvictionPolicy::Ttl)
+    }
+
+    pub fn with_eviction_policy(max_size: usize, eviction_policy: EvictionPolicy) -> Self {
         let cache = Arc::new(DashMap::new());
         let stats = Arc::new(dashmap::DashMap::new());
-        
+
         // Initialize stats
         stats.insert("hits".to_string(), 0);
         stats.insert("misses".to_string(), 0);
         stats.insert("evictions".to_string(), 0);
-        
-        let manager = Self {
+
+        Self {
             cache,
             max_size,
+            eviction_policy,
             stats,
-        };
-        
-        // Start cleanup task
-        manager.start_cleanup_task();
-        
-        manager
+        }
     }
 
     pub async fn get(&self, key: &str) -> Option<String> {
-        match self.cache.get(key) {
-            Some(entry) => {
+        match self.cache.get_mut(key) {
+            Some(mut entry) => {
                 if entry.expires_at > Instant::now() {
-                    // Cache hit
+                    // Cache hit; record recency/frequency for the Lru/Lfu eviction policies.
+                    entry.last_accessed = Instant::now();
+                    entry.access_count += 1;
                     self.stats.entry("hits".to_string()).and_modify(|v| *v += 1);
                     Some(entry.value.clone())
                 } else {
                     // Expired entry
+                    drop(entry);
                     self.cache.remove(key);
                     self.stats.entry("misses".to_string()).and_modify(|v| *v += 1);
                     None
@@ -75,10 +122,13 @@ impl CacheManager {
             self.evict_oldest_entries().await;
         }
 
+        let now = Instant::now();
         let entry = CacheEntry {
             value,
-            expires_at: Instant::now() + ttl,
-            created_at: Instant::now(),
+            expires_at: now + ttl,
+            created_at: now,
+            last_accessed: now,
+            access_count: 0,
         };
 
         self.cache.insert(key.to_string(), entry);
@@ -89,6 +139,15 @@ impl CacheManager {
         self.cache.remove(key).is_some()
     }
 
+    /// Reads a value without touching hit/miss stats or the `Lru`/`Lfu` recency bookkeeping -
+    /// used by whole-cache introspection (the gossip anti-entropy snapshot) that shouldn't skew
+    /// metrics derived from real traffic.
+    pub async fn peek(&self, key: &str) -> Option<String> {
+        self.cache.get(key).and_then(|entry| {
+            (entry.expires_at > Instant::now()).then(|| entry.value.clone())
+        })
+    }
+
     pub async fn clear(&self) {
         self.cache.clear();
         self.stats.insert("hits".to_string(), 0);
@@ -137,68 +196,115 @@ impl CacheManager {
     }
 
     async fn evict_oldest_entries(&self) {
-        // Simple LRU-like eviction: remove entries that are close to expiration
+        // Always start by dropping entries that are already expired, regardless of policy.
         let now = Instant::now();
         let mut to_remove = Vec::new();
-        
-        // Find entries that are expired or close to expiration
+
         for entry in self.cache.iter() {
             if entry.expires_at <= now {
                 to_remove.push(entry.key().clone());
             }
         }
-        
-        // Remove expired entries
+
         for key in to_remove {
             if self.cache.remove(&key).is_some() {
                 self.stats.entry("evictions".to_string()).and_modify(|v| *v += 1);
             }
         }
-        
-        // If still at capacity, remove oldest entries
-        if self.cache.len() >= self.max_size {
-            let mut entries: Vec<_> = self.cache.iter()
-                .map(|entry| (entry.key().clone(), entry.created_at))
-                .collect();
-            
-            entries.sort_by(|a, b| a.1.cmp(&b.1));
-            
-            let to_remove_count = (self.max_size as f64 * 0.1) as usize; // Remove 10%
-            for (key, _) in entries.iter().take(to_remove_count) {
-                if self.cache.remove(key).is_some() {
-                    self.stats.entry("evictions".to_string()).and_modify(|v| *v += 1);
-                }
+
+        if self.cache.len() < self.max_size {
+            return;
+        }
+
+        match self.eviction_policy {
+            EvictionPolicy::Ttl => self.evict_ttl_batch(),
+            EvictionPolicy::Lru => self.evict_by_sampling(|entry| entry.last_accessed),
+            EvictionPolicy::Lfu => self.evict_by_sampling(|entry| entry.access_count),
+        }
+    }
+
+    /// Original behavior: full scan, sort by `created_at`, drop the oldest 10%.
+    fn evict_ttl_batch(&self) {
+        let mut entries: Vec<_> = self.cache.iter()
+            .map(|entry| (entry.key().clone(), entry.created_at))
+            .collect();
+
+        entries.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let to_remove_count = (self.max_size as f64 * 0.1) as usize; // Remove 10%
+        for (key, _) in entries.iter().take(to_remove_count) {
+            if self.cache.remove(key).is_some() {
+                self.stats.entry("evictions".to_string()).and_modify(|v| *v += 1);
             }
         }
     }
 
-    fn start_cleanup_task(&self) {
-        let cache = self.cache.clone();
-        let stats = self.stats.clone();
-        
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(300)); // Every 5 minutes
-            
-            loop {
-                interval.tick().await;
-                
-                // Clean up expired entries
-                let now = Instant::now();
-                let mut expired_keys = Vec::new();
-                
-                for entry in cache.iter() {
-                    if entry.expires_at <= now {
-                        expired_keys.push(entry.key().clone());
-                    }
-                }
-                
-                for key in expired_keys {
-                    if cache.remove(&key).is_some() {
-                        stats.entry("evictions".to_string()).and_modify(|v| *v += 1);
-                    }
-                }
+    /// Redis-style approximate eviction for `Lru`/`Lfu`: repeatedly sample `SAMPLE_SIZE` random
+    /// keys (via a random skip into the `DashMap` iterator, which avoids a global scan/lock),
+    /// fold them into a small "eviction pool" of the best candidates seen so far ranked by
+    /// `rank`, and evict the single worst pool entry. Repeating this until below the high-water
+    /// mark converges toward true LRU/LFU without ever sorting the whole map.
+    fn evict_by_sampling<K, F>(&self, rank: F)
+    where
+        K: Ord + Copy,
+        F: Fn(&CacheEntry<String>) -> K,
+    {
+        let high_water_mark = (self.max_size as f64 * 0.9) as usize; // evict down to 90%
+        let mut pool: Vec<(String, K)> = Vec::new();
+
+        while self.cache.len() > high_water_mark {
+            let total = self.cache.len();
+            if total == 0 {
+                break;
             }
-        });
+
+            let skip = rand::thread_rng().gen_range(0..total);
+            pool.extend(
+                self.cache.iter()
+                    .skip(skip)
+                    .chain(self.cache.iter())
+                    .take(SAMPLE_SIZE)
+                    .map(|entry| (entry.key().clone(), rank(entry.value()))),
+            );
+
+            pool.sort_by_key(|(_, rank)| *rank);
+            pool.dedup_by(|a, b| a.0 == b.0);
+            pool.truncate(EVICTION_POOL_SIZE);
+
+            let Some((key, _)) = pool.first().cloned() else {
+                break;
+            };
+            pool.remove(0);
+
+            if self.cache.remove(&key).is_some() {
+                self.stats.entry("evictions".to_string()).and_modify(|v| *v += 1);
+            }
+        }
+    }
+
+    /// Sweeps out entries that have already expired. Driven on a schedule by
+    /// `worker::CacheCleanupWorker` rather than an unsupervised `tokio::spawn` loop, so a panic
+    /// or runaway tick shows up as that worker going `Dead` instead of silently vanishing.
+    /// Returns the number of entries removed.
+    pub async fn cleanup_expired(&self) -> usize {
+        let now = Instant::now();
+        let mut expired_keys = Vec::new();
+
+        for entry in self.cache.iter() {
+            if entry.expires_at <= now {
+                expired_keys.push(entry.key().clone());
+            }
+        }
+
+        let mut removed = 0;
+        for key in &expired_keys {
+            if self.cache.remove(key).is_some() {
+                self.stats.entry("evictions".to_string()).and_modify(|v| *v += 1);
+                removed += 1;
+            }
+        }
+
+        removed
     }
 
     pub async fn warm_up(&self, entries: Vec<(String, String, Duration)>) -> Result<(), CacheError> {
@@ -252,6 +358,88 @@ pub enum CacheError {
     SerializationError(#[from] serde_json::Error),
 }
 
+/// Collapses concurrent cache misses for the same key into one upstream computation, keyed
+/// identically to the cache itself. When a popular entry expires, every request that arrives
+/// before it's recomputed would otherwise fire its own expensive RAG call (a cache stampede);
+/// here the first miss becomes the leader that runs `compute` and writes the result back, while
+/// every other caller for that key awaits the leader's broadcast instead. Mirrors
+/// `process_map::ProcessMap`'s dedup shape, but also owns the cache read/write around it so
+/// call sites no longer hand-roll the get-then-set sequence themselves.
+pub struct SingleFlight {
+    inflight: DashMap<String, broadcast::Sender<Arc<Result<String, StatusCode>>>>,
+}
+
+impl SingleFlight {
+    pub fn new() -> Self {
+        Self {
+            inflight: DashMap::new(),
+        }
+    }
+
+    /// Returns the cached value for `key` if present and `caching_enabled`; otherwise runs
+    /// `compute` (deduped across concurrent callers) and, if it succeeds and caching is enabled,
+    /// writes the result back with `ttl` before returning it. The `bool` in the result is `true`
+    /// only when the value was already in the cache.
+    pub async fn get_or_compute<F, Fut>(
+        &self,
+        cache: &Arc<dyn CacheBackend>,
+        key: &str,
+        ttl: Duration,
+        caching_enabled: bool,
+        compute: F,
+    ) -> Result<(String, bool), StatusCode>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, StatusCode>>,
+    {
+        if caching_enabled {
+            if let Some(cached) = cache.get(key).await {
+                return Ok((cached, true));
+            }
+        }
+
+        enum Role {
+            Leader(broadcast::Sender<Arc<Result<String, StatusCode>>>),
+            Follower(broadcast::Receiver<Arc<Result<String, StatusCode>>>),
+        }
+
+        let role = match self.inflight.entry(key.to_string()) {
+            Entry::Occupied(entry) => Role::Follower(entry.get().subscribe()),
+            Entry::Vacant(entry) => {
+                let (sender, _receiver) = broadcast::channel(16);
+                entry.insert(sender.clone());
+                Role::Leader(sender)
+            }
+        };
+
+        match role {
+            Role::Leader(sender) => {
+                let result = compute().await;
+                // Remove before broadcasting (and on error too) so a failed leader doesn't
+                // poison the map for the next request with this key.
+                self.inflight.remove(key);
+                if caching_enabled {
+                    if let Ok(value) = &result {
+                        cache.put(key, value.clone(), ttl).await;
+                    }
+                }
+                let _ = sender.send(Arc::new(result.clone()));
+                result.map(|value| (value, false))
+            }
+            Role::Follower(mut receiver) => match receiver.recv().await {
+                Ok(result) => (*result).clone().map(|value| (value, false)),
+                Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+            },
+        }
+    }
+}
+
+impl Default for SingleFlight {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +498,31 @@ mod tests {
         assert_eq!(stats.hits, 1);
         assert_eq!(stats.misses, 1);
     }
+
+    #[test]
+    fn test_eviction_policy_parse() {
+        assert_eq!(EvictionPolicy::parse("lru"), EvictionPolicy::Lru);
+        assert_eq!(EvictionPolicy::parse("LFU"), EvictionPolicy::Lfu);
+        assert_eq!(EvictionPolicy::parse("ttl"), EvictionPolicy::Ttl);
+        assert_eq!(EvictionPolicy::parse("unknown"), EvictionPolicy::Ttl);
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction_prefers_least_recently_used() {
+        let cache = CacheManager::with_eviction_policy(10, EvictionPolicy::Lru);
+
+        for i in 0..10 {
+            cache.set(&format!("key{i}"), format!("value{i}"), Duration::from_secs(60)).await.unwrap();
+        }
+
+        // Keep every key but key0 warm so it is the clear LRU candidate.
+        for i in 1..10 {
+            cache.get(&format!("key{i}")).await;
+        }
+
+        // Pushing the cache over capacity should converge on evicting the coldest entry.
+        cache.set("key10", "value10".to_string(), Duration::from_secs(60)).await.unwrap();
+
+        assert_eq!(cache.get("key0").await, None);
+    }
 }
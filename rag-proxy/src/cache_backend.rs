@@ -0,0 +1,241 @@
+use crate::cache::{CacheManager, CacheStats, EvictionPolicy};
+use crate::config::{Config, SupabaseConfig};
+use crate::error::RAGProxyError;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Uniform interface over whatever actually durably (or not) stores cached suggestions.
+/// `AppState` holds one `Arc<dyn CacheBackend>` so `suggest_code` and friends don't care
+/// whether entries live in-process, in Redis, or in the Supabase/Postgres table already
+/// described by `SupabaseConfig` - only `Config::cache.backend` decides that at startup.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn put(&self, key: &str, value: String, ttl: Duration);
+    async fn invalidate(&self, key: &str);
+
+    /// Detailed hit/miss/eviction/memory counters for `/metrics`, scraped straight from the
+    /// backend's own bookkeeping. `Redis`/`Supabase` have no equivalent to report cheaply, so
+    /// they fall back to `None` and `/metrics` just omits the cache_* series for those backends.
+    async fn stats(&self) -> Option<CacheStats> {
+        None
+    }
+
+    /// Sweeps already-expired entries, driven periodically by `worker::CacheCleanupWorker`.
+    /// `Redis`/`Supabase` expire natively (`SET ... EX`, a stored `expires_at` column) so this
+    /// is a no-op for them; only `InMemoryBackend` needs an active sweep.
+    async fn cleanup_expired(&self) -> usize {
+        0
+    }
+
+    /// Drops every entry. `Redis`/`Supabase` would need a `FLUSHDB`/`TRUNCATE`-equivalent to
+    /// support this cheaply, so they default to a no-op; only `InMemoryBackend` overrides it.
+    async fn clear(&self) {}
+
+    /// Drops every entry whose key starts with `prefix`, returning the removed keys. Used by
+    /// `learn_from_code` to invalidate stale suggestions for a file without wiping the whole
+    /// cache. Defaults to a no-op for backends with no cheap way to enumerate keys.
+    async fn invalidate_prefix(&self, _prefix: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Dumps every live `(key, value, remaining_ttl)` triple. Used by gossip anti-entropy to
+    /// reconcile a peer whose digest doesn't match; defaults to empty for backends that can't
+    /// cheaply enumerate their full contents.
+    async fn snapshot(&self) -> Vec<(String, String, Duration)> {
+        Vec::new()
+    }
+}
+
+/// Wraps the existing TTL-aware `CacheManager` so it can be selected via `CACHE_BACKEND=memory`
+/// (the default) without touching callers.
+pub struct InMemoryBackend {
+    manager: Arc<CacheManager>,
+}
+
+impl InMemoryBackend {
+    pub fn new(max_size: usize, eviction_policy: EvictionPolicy) -> Self {
+        Self {
+            manager: Arc::new(CacheManager::with_eviction_policy(max_size, eviction_policy)),
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for InMemoryBackend {
+    async fn get(&self, key: &str) -> Option<String> {
+        self.manager.get(key).await
+    }
+
+    async fn put(&self, key: &str, value: String, ttl: Duration) {
+        let _ = self.manager.set(key, value, ttl).await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        self.manager.remove(key).await;
+    }
+
+    async fn stats(&self) -> Option<CacheStats> {
+        Some(self.manager.get_stats().await)
+    }
+
+    async fn cleanup_expired(&self) -> usize {
+        self.manager.cleanup_expired().await
+    }
+
+    async fn clear(&self) {
+        self.manager.clear().await;
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut removed = Vec::new();
+        for key in self.manager.get_keys().await {
+            if key.starts_with(prefix) && self.manager.remove(&key).await {
+                removed.push(key);
+            }
+        }
+        removed
+    }
+
+    async fn snapshot(&self) -> Vec<(String, String, Duration)> {
+        let mut entries = Vec::new();
+        for key in self.manager.get_keys().await {
+            if let (Some(value), Some(ttl)) =
+                (self.manager.peek(&key).await, self.manager.get_ttl(&key).await)
+            {
+                entries.push((key, value, ttl));
+            }
+        }
+        entries
+    }
+}
+
+/// Redis-backed cache for horizontally-scaled proxy instances that need a shared suggestion
+/// cache. Connects lazily via a `redis::Client` and relies on Redis's own `SET ... EX` / `GET`
+/// for TTL handling rather than re-implementing expiry locally.
+pub struct RedisBackend {
+    client: redis::Client,
+}
+
+impl RedisBackend {
+    pub fn new(url: &str) -> Result<Self, RAGProxyError> {
+        let client = redis::Client::open(url)
+            .map_err(|e| RAGProxyError::configuration_error(format!("invalid Redis URL: {}", e)))?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisBackend {
+    async fn get(&self, key: &str) -> Option<String> {
+        let mut conn = self.client.get_multiplexed_async_connection().await.ok()?;
+        redis::cmd("GET").arg(key).query_async(&mut conn).await.ok()
+    }
+
+    async fn put(&self, key: &str, value: String, ttl: Duration) {
+        let Ok(mut conn) = self.client.get_multiplexed_async_connection().await else {
+            return;
+        };
+        let _: Result<(), _> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async(&mut conn)
+            .await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        let Ok(mut conn) = self.client.get_multiplexed_async_connection().await else {
+            return;
+        };
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query_async(&mut conn).await;
+    }
+}
+
+/// Supabase/Postgres-backed cache, reusing the already-present `SupabaseConfig` credentials.
+/// Suggestions are persisted to a `rag_cache(key text primary key, value text, expires_at
+/// timestamptz)` table via PostgREST so the cache survives proxy restarts and is shared
+/// across replicas without requiring a separate Redis deployment.
+pub struct SupabaseBackend {
+    http: reqwest::Client,
+    rest_url: String,
+    service_key: String,
+}
+
+impl SupabaseBackend {
+    pub fn new(config: &SupabaseConfig) -> Result<Self, RAGProxyError> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()?;
+        Ok(Self {
+            http,
+            rest_url: format!("{}/rest/v1/rag_cache", config.url),
+            service_key: config.service_key.expose().to_string(),
+        })
+    }
+
+    fn auth_headers(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        builder
+            .header("apikey", &self.service_key)
+            .header("Authorization", format!("Bearer {}", self.service_key))
+    }
+}
+
+#[async_trait]
+impl CacheBackend for SupabaseBackend {
+    async fn get(&self, key: &str) -> Option<String> {
+        let request = self.http.get(&self.rest_url).query(&[
+            ("key", format!("eq.{}", key)),
+            ("select", "value,expires_at".to_string()),
+        ]);
+        let response = self.auth_headers(request).send().await.ok()?;
+        let rows: Vec<serde_json::Value> = response.json().await.ok()?;
+        let row = rows.first()?;
+
+        let expires_at = row.get("expires_at")?.as_str()?;
+        let expires_at = sqlx::types::chrono::DateTime::parse_from_rfc3339(expires_at).ok()?;
+        if expires_at < sqlx::types::chrono::Utc::now() {
+            return None;
+        }
+
+        row.get("value")?.as_str().map(|s| s.to_string())
+    }
+
+    async fn put(&self, key: &str, value: String, ttl: Duration) {
+        let expires_at = sqlx::types::chrono::Utc::now() + sqlx::types::chrono::Duration::seconds(ttl.as_secs() as i64);
+        let body = serde_json::json!({
+            "key": key,
+            "value": value,
+            "expires_at": expires_at.to_rfc3339(),
+        });
+
+        let request = self
+            .http
+            .post(&self.rest_url)
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&body);
+        let _ = self.auth_headers(request).send().await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        let request = self
+            .http
+            .delete(&self.rest_url)
+            .query(&[("key", format!("eq.{}", key))]);
+        let _ = self.auth_headers(request).send().await;
+    }
+}
+
+/// Builds the configured backend for `AppState.cache`, selected by `Config.cache.backend`.
+pub fn build_cache_backend(config: &Config) -> Result<Arc<dyn CacheBackend>, RAGProxyError> {
+    match config.cache.backend.as_str() {
+        "redis" => Ok(Arc::new(RedisBackend::new(&config.cache.redis_url)?)),
+        "supabase" => Ok(Arc::new(SupabaseBackend::new(&config.supabase)?)),
+        _ => Ok(Arc::new(InMemoryBackend::new(
+            config.cache.max_size,
+            EvictionPolicy::parse(&config.cache.eviction_policy),
+        ))),
+    }
+}
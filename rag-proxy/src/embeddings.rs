@@ -0,0 +1,234 @@
+use crate::error::RAGProxyError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// Target chunk size and overlap, in whitespace-separated tokens, used when splitting a
+/// Memory Bank markdown file for embedding.
+const CHUNK_TOKENS: usize = 500;
+const CHUNK_OVERLAP: usize = 50;
+const DEFAULT_TOP_K: usize = 3;
+
+/// One embedded chunk of a Memory Bank file, persisted as a line of `embeddings.jsonl` so the
+/// index survives restarts and only changed files need to be re-embedded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkRecord {
+    file: String,
+    chunk_range: (usize, usize),
+    mtime_unix_secs: u64,
+    text: String,
+    vector: Vec<f32>,
+}
+
+/// Vector index over the Memory Bank's markdown files, replacing substring matching with
+/// cosine-similarity retrieval. `reindex` is incremental: a file is only re-chunked and
+/// re-embedded when its mtime has moved past what's recorded in the sidecar file.
+pub struct EmbeddingIndex {
+    memory_bank_path: PathBuf,
+    embeddings_url: String,
+    http: reqwest::Client,
+}
+
+impl EmbeddingIndex {
+    pub fn new(memory_bank_path: PathBuf) -> Self {
+        let embeddings_url = std::env::var("EMBEDDINGS_URL").unwrap_or_else(|_| {
+            let lightrag_url = std::env::var("LIGHTRAG_URL")
+                .unwrap_or_else(|_| "http://localhost:8000".to_string());
+            format!("{}/embeddings", lightrag_url)
+        });
+
+        Self {
+            memory_bank_path,
+            embeddings_url,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn sidecar_path(&self) -> PathBuf {
+        self.memory_bank_path.join("embeddings.jsonl")
+    }
+
+    /// Returns the top-k most similar chunks to `query`, concatenated into one string, or
+    /// `None` only when the index has no chunks to rank (e.g. no markdown files exist yet).
+    pub async fn query(&self, query: &str, top_k: usize) -> Result<Option<String>, RAGProxyError> {
+        self.reindex().await?;
+
+        let records = self.load_records().await?;
+        if records.is_empty() {
+            return Ok(None);
+        }
+
+        let query_vector = self.embed(query).await?;
+        let mut scored: Vec<(f32, &ChunkRecord)> = records
+            .iter()
+            .map(|record| (cosine_similarity(&query_vector, &record.vector), record))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let context = scored
+            .into_iter()
+            .take(top_k.max(1))
+            .map(|(_, record)| record.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(Some(context))
+    }
+
+    /// Re-chunks and re-embeds any tracked markdown file whose mtime has advanced past what's
+    /// recorded in `embeddings.jsonl`; files that haven't changed keep their existing chunks.
+    async fn reindex(&self) -> Result<(), RAGProxyError> {
+        let existing = self.load_records().await?;
+        let mut by_file: std::collections::HashMap<String, Vec<ChunkRecord>> =
+            std::collections::HashMap::new();
+        for record in existing {
+            by_file.entry(record.file.clone()).or_default().push(record);
+        }
+
+        let mut updated = Vec::new();
+        for file in self.tracked_files().await {
+            let Ok(metadata) = fs::metadata(&file).await else {
+                continue;
+            };
+            let mtime_unix_secs = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            let file_key = file.to_string_lossy().to_string();
+            let current = by_file.remove(&file_key).unwrap_or_default();
+            if current.first().is_some_and(|r| r.mtime_unix_secs == mtime_unix_secs) {
+                updated.extend(current);
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(&file).await else {
+                continue;
+            };
+            for (start, end, text) in chunk_text(&content) {
+                let vector = self.embed(&text).await?;
+                updated.push(ChunkRecord {
+                    file: file_key.clone(),
+                    chunk_range: (start, end),
+                    mtime_unix_secs,
+                    text,
+                    vector,
+                });
+            }
+        }
+
+        self.save_records(&updated).await
+    }
+
+    /// Top-level essential files plus every `.md` file under `creative/`, `reflection/`, and
+    /// `archive/`, mirroring the layout `MemoryBankClient::initialize` creates.
+    async fn tracked_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = ["tasks.md", "activeContext.md", "progress.md"]
+            .into_iter()
+            .map(|f| self.memory_bank_path.join(f))
+            .collect();
+
+        for subdir in ["creative", "reflection", "archive"] {
+            let dir = self.memory_bank_path.join(subdir);
+            let Ok(mut entries) = fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) == Some("md") {
+                    files.push(path);
+                }
+            }
+        }
+
+        files
+    }
+
+    async fn load_records(&self) -> Result<Vec<ChunkRecord>, RAGProxyError> {
+        let path = self.sidecar_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).await?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    async fn save_records(&self, records: &[ChunkRecord]) -> Result<(), RAGProxyError> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.sidecar_path())
+            .await?;
+        for record in records {
+            file.write_all(serde_json::to_string(record)?.as_bytes()).await?;
+            file.write_all(b"\n").await?;
+        }
+        Ok(())
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, RAGProxyError> {
+        let response = self
+            .http
+            .post(&self.embeddings_url)
+            .json(&serde_json::json!({ "input": text }))
+            .send()
+            .await?;
+
+        let body: serde_json::Value = response.json().await?;
+        let vector = body
+            .get("embedding")
+            .or_else(|| body.get("vector"))
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| RAGProxyError::service_error("embeddings endpoint returned no vector"))?
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .map(|v| v as f32)
+            .collect();
+
+        Ok(vector)
+    }
+}
+
+/// Splits whitespace-tokenized `text` into overlapping `(start, end, chunk)` windows of
+/// `CHUNK_TOKENS` tokens with `CHUNK_OVERLAP` tokens shared between consecutive chunks.
+fn chunk_text(text: &str) -> Vec<(usize, usize, String)> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + CHUNK_TOKENS).min(words.len());
+        chunks.push((start, end, words[start..end].join(" ")));
+        if end == words.len() {
+            break;
+        }
+        start += CHUNK_TOKENS - CHUNK_OVERLAP;
+    }
+    chunks
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+pub const DEFAULT_RESULT_COUNT: usize = DEFAULT_TOP_K;
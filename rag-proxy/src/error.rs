@@ -37,7 +37,10 @@ pub enum RAGProxyError {
     
     #[error("Validation error: {0}")]
     ValidationError(String),
-    
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("Authentication error: {0}")]
     AuthenticationError(String),
     
@@ -76,7 +79,11 @@ impl RAGProxyError {
     pub fn validation_error<S: Into<String>>(message: S) -> Self {
         Self::ValidationError(message.into())
     }
-    
+
+    pub fn payload_too_large<S: Into<String>>(message: S) -> Self {
+        Self::PayloadTooLarge(message.into())
+    }
+
     pub fn authentication_error<S: Into<String>>(message: S) -> Self {
         Self::AuthenticationError(message.into())
     }
@@ -92,7 +99,9 @@ impl RAGProxyError {
     pub fn status_code(&self) -> axum::http::StatusCode {
         match self {
             Self::ServiceUnavailable(_) => axum::http::StatusCode::SERVICE_UNAVAILABLE,
-            Self::ServiceError(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            // Upstream (LightRAG/n8n/Supabase) rejected or failed the request - distinct from a
+            // bug in the proxy itself, so callers should see 502 rather than 500.
+            Self::ServiceError(_) => axum::http::StatusCode::BAD_GATEWAY,
             Self::ConfigurationError(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             Self::MemoryBankError(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             Self::CacheError(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -103,6 +112,7 @@ impl RAGProxyError {
             Self::ParseError(_) => axum::http::StatusCode::BAD_REQUEST,
             Self::TimeoutError(_) => axum::http::StatusCode::REQUEST_TIMEOUT,
             Self::ValidationError(_) => axum::http::StatusCode::BAD_REQUEST,
+            Self::PayloadTooLarge(_) => axum::http::StatusCode::PAYLOAD_TOO_LARGE,
             Self::AuthenticationError(_) => axum::http::StatusCode::UNAUTHORIZED,
             Self::RateLimitError(_) => axum::http::StatusCode::TOO_MANY_REQUESTS,
             Self::Unknown(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -123,6 +133,7 @@ impl RAGProxyError {
             Self::ParseError(_) => "PARSE_ERROR",
             Self::TimeoutError(_) => "TIMEOUT_ERROR",
             Self::ValidationError(_) => "VALIDATION_ERROR",
+            Self::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
             Self::AuthenticationError(_) => "AUTHENTICATION_ERROR",
             Self::RateLimitError(_) => "RATE_LIMIT_ERROR",
             Self::Unknown(_) => "UNKNOWN_ERROR",
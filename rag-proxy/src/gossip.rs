@@ -0,0 +1,327 @@
+use crate::cache_backend::CacheBackend;
+use crate::config::GossipConfig;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tracing::{debug, warn};
+
+/// A single cache mutation propagated to peers. `Set` carries the TTL in whole seconds since
+/// `Duration` itself isn't directly `Serialize`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum GossipOp {
+    Set { key: String, value: String, ttl_seconds: u64 },
+    Invalidate { key: String },
+    Clear,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GossipMessage {
+    origin_id: String,
+    lamport_ts: u64,
+    op: GossipOp,
+}
+
+/// Anti-entropy heartbeat: a hash of the sender's full key set. A mismatch with the receiver's
+/// own hash triggers a full snapshot push rather than a per-key diff, which is judged to be
+/// plenty for this crate's target cache sizes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DigestMessage {
+    origin_id: String,
+    key_hash: u64,
+}
+
+/// Full-state push used to reconcile a peer whose digest didn't match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotMessage {
+    origin_id: String,
+    entries: Vec<(String, String, u64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum WireMessage {
+    Op(GossipMessage),
+    Digest(DigestMessage),
+    Snapshot(SnapshotMessage),
+}
+
+/// Drives UDP-based gossip so `CacheManager`/`CacheBackend` mutations on one proxy instance
+/// propagate to its peers. Applies incoming ops straight to the *inner* (non-gossip-wrapped)
+/// backend - `GossipedCacheBackend` is what broadcasts on local writes, so applying here must
+/// not also broadcast or every update would echo forever.
+pub struct GossipNode {
+    origin_id: String,
+    socket: Arc<UdpSocket>,
+    peers: Vec<String>,
+    inner: Arc<dyn CacheBackend>,
+    clock: AtomicU64,
+    /// Last-applied Lamport timestamp per key, used to drop stale/out-of-order updates.
+    applied: DashMap<String, u64>,
+}
+
+impl GossipNode {
+    /// Binds the gossip socket and spawns the receive and anti-entropy loops. Returns `None`
+    /// when gossip is disabled so callers can skip wrapping the cache entirely.
+    pub async fn start(
+        config: &GossipConfig,
+        inner: Arc<dyn CacheBackend>,
+    ) -> Result<Option<Arc<Self>>, std::io::Error> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let socket = UdpSocket::bind(&config.bind_addr).await?;
+        let node = Arc::new(Self {
+            origin_id: generate_origin_id(),
+            socket: Arc::new(socket),
+            peers: config.peers.clone(),
+            inner,
+            clock: AtomicU64::new(0),
+            applied: DashMap::new(),
+        });
+
+        tokio::spawn(node.clone().receive_loop());
+        tokio::spawn(node.clone().digest_loop(Duration::from_secs(config.digest_interval_seconds.max(1))));
+
+        Ok(Some(node))
+    }
+
+    fn next_ts(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub async fn broadcast_set(&self, key: &str, value: &str, ttl: Duration) {
+        let lamport_ts = self.next_ts();
+        self.applied.insert(key.to_string(), lamport_ts);
+        self.send_to_peers(&WireMessage::Op(GossipMessage {
+            origin_id: self.origin_id.clone(),
+            lamport_ts,
+            op: GossipOp::Set {
+                key: key.to_string(),
+                value: value.to_string(),
+                ttl_seconds: ttl.as_secs().max(1),
+            },
+        }))
+        .await;
+    }
+
+    pub async fn broadcast_invalidate(&self, key: &str) {
+        let lamport_ts = self.next_ts();
+        self.applied.insert(key.to_string(), lamport_ts);
+        self.send_to_peers(&WireMessage::Op(GossipMessage {
+            origin_id: self.origin_id.clone(),
+            lamport_ts,
+            op: GossipOp::Invalidate { key: key.to_string() },
+        }))
+        .await;
+    }
+
+    pub async fn broadcast_clear(&self) {
+        let lamport_ts = self.next_ts();
+        self.send_to_peers(&WireMessage::Op(GossipMessage {
+            origin_id: self.origin_id.clone(),
+            lamport_ts,
+            op: GossipOp::Clear,
+        }))
+        .await;
+    }
+
+    async fn send_to_peers(&self, message: &WireMessage) {
+        let Ok(payload) = serde_json::to_vec(message) else {
+            return;
+        };
+        for peer in &self.peers {
+            if let Err(e) = self.socket.send_to(&payload, peer).await {
+                warn!("gossip: failed to send to {}: {}", peer, e);
+            }
+        }
+    }
+
+    async fn receive_loop(self: Arc<Self>) {
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let (len, _from) = match self.socket.recv_from(&mut buf).await {
+                Ok(pair) => pair,
+                Err(e) => {
+                    warn!("gossip: recv error: {}", e);
+                    continue;
+                }
+            };
+
+            let Ok(message) = serde_json::from_slice::<WireMessage>(&buf[..len]) else {
+                continue;
+            };
+
+            match message {
+                WireMessage::Op(op) => self.apply(op).await,
+                WireMessage::Digest(digest) => self.handle_digest(digest).await,
+                WireMessage::Snapshot(snapshot) => self.apply_snapshot(snapshot).await,
+            }
+        }
+    }
+
+    async fn apply(&self, message: GossipMessage) {
+        if message.origin_id == self.origin_id {
+            return;
+        }
+
+        // Merge the sender's Lamport timestamp into our own clock (the standard Lamport receive
+        // rule) so the next local `next_ts()` is guaranteed to be causally after anything we've
+        // seen. Without this, each node's counter advances independently and `lamport_ts`
+        // comparisons below are meaningless across nodes - a node that just wrote a key locally
+        // could hold a higher `applied` value than a peer's genuinely newer write and drop it.
+        self.clock.fetch_max(message.lamport_ts, Ordering::Relaxed);
+
+        let op_key = match &message.op {
+            GossipOp::Set { key, .. } => Some(key.clone()),
+            GossipOp::Invalidate { key } => Some(key.clone()),
+            GossipOp::Clear => None,
+        };
+
+        if let Some(key) = &op_key {
+            let is_newer = self
+                .applied
+                .get(key)
+                .map(|existing| message.lamport_ts > *existing)
+                .unwrap_or(true);
+            if !is_newer {
+                debug!("gossip: dropping stale update for {}", key);
+                return;
+            }
+            self.applied.insert(key.clone(), message.lamport_ts);
+        }
+
+        match message.op {
+            GossipOp::Set { key, value, ttl_seconds } => {
+                self.inner.put(&key, value, Duration::from_secs(ttl_seconds)).await;
+            }
+            GossipOp::Invalidate { key } => {
+                self.inner.invalidate(&key).await;
+            }
+            GossipOp::Clear => {
+                self.inner.clear().await;
+            }
+        }
+    }
+
+    async fn digest_loop(self: Arc<Self>, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            let snapshot = self.inner.snapshot().await;
+            let key_hash = hash_keys(snapshot.iter().map(|(k, _, _)| k.as_str()));
+            self.send_to_peers(&WireMessage::Digest(DigestMessage {
+                origin_id: self.origin_id.clone(),
+                key_hash,
+            }))
+            .await;
+        }
+    }
+
+    async fn handle_digest(&self, digest: DigestMessage) {
+        if digest.origin_id == self.origin_id {
+            return;
+        }
+
+        let snapshot = self.inner.snapshot().await;
+        let local_hash = hash_keys(snapshot.iter().map(|(k, _, _)| k.as_str()));
+        if local_hash == digest.key_hash {
+            return;
+        }
+
+        debug!("gossip: digest mismatch with {}, pushing full snapshot", digest.origin_id);
+        let entries = snapshot
+            .into_iter()
+            .map(|(key, value, ttl)| (key, value, ttl.as_secs().max(1)))
+            .collect();
+        self.send_to_peers(&WireMessage::Snapshot(SnapshotMessage {
+            origin_id: self.origin_id.clone(),
+            entries,
+        }))
+        .await;
+    }
+
+    async fn apply_snapshot(&self, snapshot: SnapshotMessage) {
+        if snapshot.origin_id == self.origin_id {
+            return;
+        }
+        for (key, value, ttl_seconds) in snapshot.entries {
+            self.inner.put(&key, value, Duration::from_secs(ttl_seconds)).await;
+        }
+    }
+}
+
+fn hash_keys<'a>(keys: impl Iterator<Item = &'a str>) -> u64 {
+    let mut sorted: Vec<&str> = keys.collect();
+    sorted.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    for key in sorted {
+        key.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn generate_origin_id() -> String {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    (0..16).map(|_| format!("{:x}", rng.gen_range(0..16))).collect()
+}
+
+/// Wraps an inner `CacheBackend` so every local write also broadcasts to gossip peers. Reads
+/// are delegated unchanged; nothing else in the crate needs to know gossip is active.
+pub struct GossipedCacheBackend {
+    inner: Arc<dyn CacheBackend>,
+    node: Arc<GossipNode>,
+}
+
+impl GossipedCacheBackend {
+    pub fn new(inner: Arc<dyn CacheBackend>, node: Arc<GossipNode>) -> Self {
+        Self { inner, node }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for GossipedCacheBackend {
+    async fn get(&self, key: &str) -> Option<String> {
+        self.inner.get(key).await
+    }
+
+    async fn put(&self, key: &str, value: String, ttl: Duration) {
+        self.inner.put(key, value.clone(), ttl).await;
+        self.node.broadcast_set(key, &value, ttl).await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        self.inner.invalidate(key).await;
+        self.node.broadcast_invalidate(key).await;
+    }
+
+    async fn stats(&self) -> Option<crate::cache::CacheStats> {
+        self.inner.stats().await
+    }
+
+    async fn cleanup_expired(&self) -> usize {
+        self.inner.cleanup_expired().await
+    }
+
+    async fn clear(&self) {
+        self.inner.clear().await;
+        self.node.broadcast_clear().await;
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) -> Vec<String> {
+        let removed = self.inner.invalidate_prefix(prefix).await;
+        for key in &removed {
+            self.node.broadcast_invalidate(key).await;
+        }
+        removed
+    }
+
+    async fn snapshot(&self) -> Vec<(String, String, Duration)> {
+        self.inner.snapshot().await
+    }
+}
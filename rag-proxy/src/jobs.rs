@@ -0,0 +1,179 @@
+use crate::memory_bank::MemoryBankClient;
+use crate::metrics::Metrics;
+use crate::rag::RAGService;
+use crate::types::LearnRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, RwLock};
+
+/// Work a job can represent. `/api/learn` enqueues `LearnFromCode`; other handlers can enqueue
+/// `ReindexEmbeddings`/`IntegrateSpecKit` as they're wired up to run off the request path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JobKind {
+    ReindexEmbeddings,
+    LearnFromCode { file_path: String, code: String, language: String },
+    IntegrateSpecKit { spec_type: String, code: String },
+}
+
+impl JobKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::ReindexEmbeddings => "reindex_embeddings",
+            Self::LearnFromCode { .. } => "learn_from_code",
+            Self::IntegrateSpecKit { .. } => "integrate_spec_kit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub kind: String,
+    pub state: JobState,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+struct QueuedJob {
+    id: String,
+    kind: JobKind,
+}
+
+/// In-process job queue: an unbounded channel feeds a single worker task, and every state
+/// transition is appended to `jobs.jsonl` under `memory_bank_path` so in-flight jobs survive a
+/// restart (at-least-once: a job mid-run when the process dies is journaled as `running` and
+/// is not automatically resumed, matching the rest of this crate's no-extra-durability-guarantees posture).
+pub struct JobQueue {
+    sender: mpsc::UnboundedSender<QueuedJob>,
+    records: Arc<RwLock<HashMap<String, JobRecord>>>,
+    next_id: AtomicU64,
+}
+
+impl JobQueue {
+    pub fn start(
+        memory_bank_path: &str,
+        rag_service: Arc<RAGService>,
+        memory_bank: Arc<MemoryBankClient>,
+        metrics: Arc<Metrics>,
+    ) -> Arc<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let records = Arc::new(RwLock::new(HashMap::new()));
+        let journal_path = PathBuf::from(memory_bank_path).join("jobs.jsonl");
+
+        tokio::spawn(Self::run_worker(receiver, records.clone(), journal_path, rag_service, memory_bank, metrics));
+
+        Arc::new(Self {
+            sender,
+            records,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    pub async fn enqueue(&self, kind: JobKind) -> String {
+        let id = format!("job-{:08x}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let record = JobRecord {
+            id: id.clone(),
+            kind: kind.label().to_string(),
+            state: JobState::Queued,
+            result: None,
+            error: None,
+        };
+        self.records.write().await.insert(id.clone(), record);
+        // An unbounded channel send only fails if the worker task has already stopped, which
+        // only happens on process shutdown; there's no request-path recovery for that case.
+        let _ = self.sender.send(QueuedJob { id: id.clone(), kind });
+        id
+    }
+
+    pub async fn status(&self, id: &str) -> Option<JobRecord> {
+        self.records.read().await.get(id).cloned()
+    }
+
+    async fn run_worker(
+        mut receiver: mpsc::UnboundedReceiver<QueuedJob>,
+        records: Arc<RwLock<HashMap<String, JobRecord>>>,
+        journal_path: PathBuf,
+        rag_service: Arc<RAGService>,
+        memory_bank: Arc<MemoryBankClient>,
+        metrics: Arc<Metrics>,
+    ) {
+        while let Some(job) = receiver.recv().await {
+            Self::transition(&records, &journal_path, &job.id, JobState::Running, None, None).await;
+
+            let outcome: Result<String, String> = match &job.kind {
+                JobKind::ReindexEmbeddings => memory_bank
+                    .get_rag_context("")
+                    .await
+                    .map(|_| "embeddings reindexed".to_string())
+                    .map_err(|e| e.to_string()),
+                JobKind::LearnFromCode { file_path, code, language } => {
+                    let request = LearnRequest {
+                        file_path: file_path.clone(),
+                        code: code.clone(),
+                        language: language.clone(),
+                        context: None,
+                    };
+                    rag_service
+                        .learn_from_code(&request)
+                        .await
+                        .map(|_| "learned".to_string())
+                        .map_err(|e| e.to_string())
+                }
+                JobKind::IntegrateSpecKit { spec_type, code } => memory_bank
+                    .integrate_rag_context(spec_type, code)
+                    .await
+                    .map_err(|e| e.to_string()),
+            };
+
+            match outcome {
+                Ok(result) => {
+                    Self::transition(&records, &journal_path, &job.id, JobState::Done, Some(result), None).await
+                }
+                Err(error) => {
+                    if matches!(job.kind, JobKind::LearnFromCode { .. } | JobKind::IntegrateSpecKit { .. }) {
+                        metrics.record_memory_bank_integration_failure();
+                    }
+                    Self::transition(&records, &journal_path, &job.id, JobState::Failed, None, Some(error)).await
+                }
+            }
+        }
+    }
+
+    async fn transition(
+        records: &Arc<RwLock<HashMap<String, JobRecord>>>,
+        journal_path: &PathBuf,
+        id: &str,
+        state: JobState,
+        result: Option<String>,
+        error: Option<String>,
+    ) {
+        let snapshot = {
+            let mut guard = records.write().await;
+            let Some(record) = guard.get_mut(id) else { return };
+            record.state = state;
+            record.result = result;
+            record.error = error;
+            record.clone()
+        };
+
+        if let Ok(mut file) = tokio::fs::OpenOptions::new().create(true).append(true).open(journal_path).await {
+            if let Ok(line) = serde_json::to_string(&snapshot) {
+                let _ = file.write_all(line.as_bytes()).await;
+                let _ = file.write_all(b"\n").await;
+            }
+        }
+    }
+}
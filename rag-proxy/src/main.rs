@@ -1,37 +1,64 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::{StatusCode, Method},
-    response::IntoResponse,
+    response::sse::{Event, KeepAlive, Sse},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
-// use serde::{Deserialize, Serialize}; // Unused imports
+use serde::Deserialize;
+use futures::StreamExt;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use tracing::{info, error};
 use tower_http::cors::{CorsLayer, Any};
+use tower_http::compression::{predicate::SizeAbove, CompressionLayer};
 
+mod auth;
 mod config;
+mod embeddings;
+mod jobs;
 mod memory_bank;
 mod rag;
 mod cache;
+mod cache_backend;
 mod error;
+mod gossip;
+mod metrics;
+mod process_map;
+mod store;
 mod types;
+mod validation;
+mod worker;
 
 use types::*;
 use crate::config::Config;
+use crate::error::ErrorResponse;
+use crate::jobs::{JobKind, JobQueue};
 use crate::memory_bank::MemoryBankClient;
 use crate::rag::RAGService;
-use crate::cache::CacheManager;
+use crate::cache::SingleFlight;
+use crate::cache_backend::{build_cache_backend, CacheBackend};
+use crate::gossip::{GossipNode, GossipedCacheBackend};
+use crate::metrics::{Metrics, Route, Upstream};
+use crate::process_map::ProcessMap;
+use crate::validation::{validate_code_request, validate_search_request};
+use crate::worker::{CacheCleanupWorker, WorkerCommand, WorkerManager, WorkerStatus};
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Config,
     pub memory_bank: Arc<MemoryBankClient>,
     pub rag_service: Arc<RAGService>,
-    pub cache: Arc<CacheManager>,
+    pub cache: Arc<dyn CacheBackend>,
+    pub single_flight: Arc<SingleFlight>,
+    pub metrics: Arc<Metrics>,
+    pub jobs: Arc<JobQueue>,
+    pub explain_inflight: Arc<ProcessMap<HashMap<String, String>>>,
+    pub workers: Arc<WorkerManager>,
 }
 
 #[tokio::main]
@@ -47,29 +74,61 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize services
     let memory_bank = Arc::new(MemoryBankClient::new("memory-bank")?);
     let rag_service = Arc::new(RAGService::new(&config).await?);
-    let cache = Arc::new(CacheManager::new(1000));
+    let inner_cache = build_cache_backend(&config)?;
+    let gossip = GossipNode::start(&config.gossip, inner_cache.clone()).await?;
+    let cache: Arc<dyn CacheBackend> = match &gossip {
+        Some(node) => Arc::new(GossipedCacheBackend::new(inner_cache, node.clone())),
+        None => inner_cache,
+    };
+    let metrics = Arc::new(Metrics::default());
+    let jobs = JobQueue::start(&config.memory_bank.path, rag_service.clone(), memory_bank.clone(), metrics.clone());
+
+    let workers = Arc::new(WorkerManager::new());
+    workers.register(Arc::new(CacheCleanupWorker::new(cache.clone())), Duration::from_secs(300));
 
     let app_state = AppState {
         config,
         memory_bank,
         rag_service,
         cache,
+        single_flight: Arc::new(SingleFlight::new()),
+        metrics,
+        jobs,
+        explain_inflight: Arc::new(ProcessMap::new()),
+        workers,
     };
 
     // Build router
+    let auth_state = app_state.clone();
+    let compression = app_state.config.compression.clone();
+    let compression_layer = CompressionLayer::new()
+        .gzip(compression.enabled && compression.gzip)
+        .br(compression.enabled && compression.brotli)
+        .deflate(compression.enabled && compression.deflate)
+        .zstd(compression.enabled && compression.zstd)
+        .compress_when(SizeAbove::new(compression.min_size_bytes));
     let app = Router::new()
         .route("/health", get(health_check))
+        .route("/metrics", get(metrics_handler))
         .route("/api/suggest", post(suggest_code))
+        .route("/api/suggest/stream", post(suggest_code_stream))
         .route("/api/search", post(search_context))
         .route("/api/learn", post(learn_from_code))
         .route("/api/explain", post(explain_code))
+        .route("/api/explain/stream", post(explain_code_stream))
+        .route("/api/jobs/:id", get(get_job_status))
+        .route("/api/batch", post(batch_handler))
+        .route("/api/workers", get(list_workers))
+        .route("/api/workers/:name", post(control_worker))
         .with_state(app_state)
         .layer(
             CorsLayer::new()
                 .allow_methods([Method::GET, Method::POST])
                 .allow_headers(Any)
                 .allow_origin(Any),
-        );
+        )
+        .layer(axum::middleware::from_fn_with_state(auth_state, auth::require_api_key))
+        .layer(compression_layer);
 
     // Start server
     let addr = format!("{}:{}", "0.0.0.0", "8000");
@@ -137,152 +196,506 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     })
 }
 
+/// Prometheus text-exposition endpoint. Returns 404 rather than an empty body when
+/// `CacheConfig.enable_metrics` is off, so scrapers configured against a metrics-disabled
+/// deployment fail loudly instead of ingesting a permanently-zero series.
+async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    if !state.config.cache.enable_metrics {
+        return (StatusCode::NOT_FOUND, "metrics disabled".to_string());
+    }
+    let cache_stats = state.cache.stats().await;
+    (StatusCode::OK, state.metrics.render(cache_stats.as_ref()))
+}
+
 async fn suggest_code(
     State(state): State<AppState>,
     Json(request): Json<CodeContextRequest>,
-) -> Result<Json<SuggestionResponse>, StatusCode> {
+) -> Response {
+    if let Err(err) = validate_code_request(&state.config.limits, &request.file_path, &request.code, &request.language) {
+        return (err.status_code(), Json(ErrorResponse::from(err))).into_response();
+    }
+    match suggest_impl(&state, request).await {
+        Ok(response) => Json(response).into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
+/// Shared by the `/api/suggest` handler and `/api/batch`'s `suggest` operation, so batched
+/// requests get the same cache lookups, dedup, and TTL handling as a standalone call.
+async fn suggest_impl(
+    state: &AppState,
+    request: CodeContextRequest,
+) -> Result<SuggestionResponse, StatusCode> {
     let start_time = Instant::now();
-    
-    // Check cache first
+    let ttl_seconds = state.config.rag.cache_ttl_seconds;
+    let caching_enabled = state.config.rag.enable_caching;
     let cache_key = format!("suggest:{}:{}", request.file_path, request.code.len());
-    if let Some(cached_response) = state.cache.get(&cache_key).await {
-        let suggestions: Vec<CodeSuggestion> = serde_json::from_str(&cached_response).unwrap_or_default();
-        return Ok(Json(SuggestionResponse {
-            suggestions,
-            context: request.code.clone(),
-            memory_bank_context: None,
-            cached: true,
-            processing_time_ms: start_time.elapsed().as_millis() as u64,
-        }));
-    }
 
-    // Get suggestions from RAG service
-    let suggestions = match timeout(
-        Duration::from_secs(10),
-        state.rag_service.suggest_code(&request, &None)
-    ).await {
-        Ok(Ok(suggestions)) => suggestions,
-        Ok(Err(e)) => {
-            error!("RAG service error: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-        Err(_) => {
-            error!("Request timeout");
-            return Err(StatusCode::REQUEST_TIMEOUT);
+    // Coalesces concurrent requests sharing a cache key into one LightRAG call and one cache
+    // write, so a burst of requests hitting a just-expired key doesn't stampede the upstream.
+    let (payload, cache_hit) = state
+        .single_flight
+        .get_or_compute(&state.cache, &cache_key, Duration::from_secs(ttl_seconds), caching_enabled, || async {
+            let suggestions = match timeout(Duration::from_secs(10), state.rag_service.suggest_code(&request, &None)).await {
+                Ok(Ok(suggestions)) => {
+                    state.metrics.record_upstream_request(Upstream::LightRag, false);
+                    suggestions
+                }
+                Ok(Err(e)) => {
+                    error!("RAG service error: {}", e);
+                    state.metrics.record_upstream_request(Upstream::LightRag, true);
+                    return Err(e.status_code());
+                }
+                Err(_) => {
+                    error!("Request timeout");
+                    state.metrics.record_upstream_request(Upstream::LightRag, true);
+                    state.metrics.record_upstream_timeout();
+                    return Err(StatusCode::REQUEST_TIMEOUT);
+                }
+            };
+            let envelope = CacheEnvelope::wrap(serde_json::to_string(&suggestions).unwrap_or_default());
+            Ok(serde_json::to_string(&envelope).unwrap_or_default())
+        })
+        .await?;
+
+    if caching_enabled {
+        if cache_hit {
+            state.metrics.record_cache_hit();
+        } else {
+            state.metrics.record_cache_miss();
+            state.metrics.adjust_cache_size(1);
         }
-    };
+    }
 
-    // Cache the result
-    state.cache.set(&cache_key, serde_json::to_string(&suggestions).unwrap_or_default(), Duration::from_secs(3600)).await;
+    let envelope: CacheEnvelope = serde_json::from_str(&payload).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let suggestions: Vec<CodeSuggestion> = serde_json::from_str(&envelope.payload).unwrap_or_default();
+    let cache_age_ms = cache_hit.then(|| envelope.age_ms());
 
-    Ok(Json(SuggestionResponse {
+    state.metrics.record_route(Route::Suggest, start_time.elapsed().as_millis() as u64);
+    Ok(SuggestionResponse {
         suggestions,
         context: request.code,
         memory_bank_context: None,
-        cached: false,
+        cached: cache_hit,
         processing_time_ms: start_time.elapsed().as_millis() as u64,
-    }))
+        cache_ttl_seconds: ttl_seconds,
+        cache_age_ms,
+    })
+}
+
+/// SSE variant of `suggest_code` for editors that want incremental completions. Falls back
+/// to the buffered `/api/suggest` cache entry when one is already warm, otherwise proxies
+/// LightRAG's token stream and still writes the fully-assembled result into the cache once
+/// the stream completes, so both endpoints share one cache key.
+async fn suggest_code_stream(
+    State(state): State<AppState>,
+    Json(request): Json<CodeContextRequest>,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let start_time = Instant::now();
+    let ttl_seconds = state.config.rag.cache_ttl_seconds;
+    let cache_key = format!("suggest:{}:{}", request.file_path, request.code.len());
+    let cache = state.cache.clone();
+
+    let events = async_stream::stream! {
+        if let Some(cached_response) = cache.get(&cache_key).await {
+            let suggestions: Vec<CodeSuggestion> = serde_json::from_str::<CacheEnvelope>(&cached_response)
+                .ok()
+                .and_then(|envelope| serde_json::from_str(&envelope.payload).ok())
+                .unwrap_or_default();
+            for (index, suggestion) in suggestions.iter().cloned().enumerate() {
+                let delta = SuggestionDelta { suggestion, index };
+                yield Ok(Event::default().event("suggestion").json_data(delta).unwrap_or_default());
+            }
+            let done = SuggestionStreamDone {
+                total: suggestions.len(),
+                cached: true,
+                processing_time_ms: start_time.elapsed().as_millis() as u64,
+            };
+            yield Ok(Event::default().event("done").json_data(done).unwrap_or_default());
+            return;
+        }
+
+        let stream = match state.rag_service.suggest_code_stream(&request, &None).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("RAG streaming error: {}", e);
+                yield Ok(Event::default().event("error").data(e.to_string()));
+                return;
+            }
+        };
+        tokio::pin!(stream);
+
+        let mut collected = Vec::new();
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(suggestion) => {
+                    let delta = SuggestionDelta { suggestion: suggestion.clone(), index: collected.len() };
+                    collected.push(suggestion);
+                    yield Ok(Event::default().event("suggestion").json_data(delta).unwrap_or_default());
+                }
+                Err(e) => {
+                    error!("RAG stream item error: {}", e);
+                    yield Ok(Event::default().event("error").data(e.to_string()));
+                }
+            }
+        }
+
+        // Persist the fully-assembled result under the same key the buffered endpoint uses.
+        let envelope = CacheEnvelope::wrap(serde_json::to_string(&collected).unwrap_or_default());
+        state.cache.put(&cache_key, serde_json::to_string(&envelope).unwrap_or_default(), Duration::from_secs(ttl_seconds)).await;
+
+        let done = SuggestionStreamDone {
+            total: collected.len(),
+            cached: false,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        };
+        yield Ok(Event::default().event("done").json_data(done).unwrap_or_default());
+    };
+
+    Sse::new(events).keep_alive(KeepAlive::default())
 }
 
 async fn search_context(
     State(state): State<AppState>,
     Json(request): Json<SearchRequest>,
 ) -> Result<Json<SearchResponse>, StatusCode> {
-    let _start_time = Instant::now();
+    search_impl(&state, request).await.map(Json)
+}
 
-    // Check cache first
+/// Shared by the `/api/search` handler and `/api/batch`'s `search` operation.
+async fn search_impl(state: &AppState, request: SearchRequest) -> Result<SearchResponse, StatusCode> {
+    let start_time = Instant::now();
+    let caching_enabled = state.config.rag.enable_caching;
     let cache_key = format!("search:{}", request.query);
-    if let Some(cached_response) = state.cache.get(&cache_key).await {
-        let response: SearchResponse = serde_json::from_str(&cached_response).unwrap_or_default();
-        return Ok(Json(response));
-    }
+    let limit = request.limit.unwrap_or(10);
 
-    // Perform search
-    let results = match timeout(
-        Duration::from_secs(15),
-        state.rag_service.search_context(&request.query, &request.spec_kit_context, request.limit.unwrap_or(10))
-    ).await {
-        Ok(Ok(results)) => results,
-        Ok(Err(e)) => {
-            error!("Search error: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-        Err(_) => {
-            error!("Search timeout");
-            return Err(StatusCode::REQUEST_TIMEOUT);
-        }
-    };
+    // Coalesces concurrent requests sharing a cache key into one LightRAG call and one cache
+    // write, so a burst of requests hitting a just-expired key doesn't stampede the upstream.
+    let (payload, cache_hit) = state
+        .single_flight
+        .get_or_compute(
+            &state.cache,
+            &cache_key,
+            Duration::from_secs(state.config.cache.default_ttl_seconds),
+            caching_enabled,
+            || async {
+                let results = match timeout(
+                    Duration::from_secs(15),
+                    state.rag_service.search_context(&request.query, &request.spec_kit_context, limit),
+                ).await {
+                    Ok(Ok(results)) => {
+                        state.metrics.record_upstream_request(Upstream::LightRag, false);
+                        results
+                    }
+                    Ok(Err(e)) => {
+                        error!("Search error: {}", e);
+                        state.metrics.record_upstream_request(Upstream::LightRag, true);
+                        return Err(e.status_code());
+                    }
+                    Err(_) => {
+                        error!("Search timeout");
+                        state.metrics.record_upstream_request(Upstream::LightRag, true);
+                        state.metrics.record_upstream_timeout();
+                        return Err(StatusCode::REQUEST_TIMEOUT);
+                    }
+                };
 
-    let total = results.len();
-    let response = SearchResponse {
-        results,
-        total,
-        query: request.query,
-        spec_kit_enriched: request.spec_kit_context.is_some(),
-    };
+                let total = results.len();
+                let response = SearchResponse {
+                    results,
+                    total,
+                    query: request.query.clone(),
+                    spec_kit_enriched: request.spec_kit_context.is_some(),
+                };
+                Ok(serde_json::to_string(&response).unwrap_or_default())
+            },
+        )
+        .await?;
+
+    if caching_enabled {
+        if cache_hit {
+            state.metrics.record_cache_hit();
+        } else {
+            state.metrics.record_cache_miss();
+            state.metrics.adjust_cache_size(1);
+        }
+    }
 
-    // Cache the result
-    state.cache.set(&cache_key, serde_json::to_string(&response).unwrap_or_default(), Duration::from_secs(1800)).await;
+    let response: SearchResponse = serde_json::from_str(&payload).unwrap_or_default();
+    state.metrics.record_route(Route::Search, start_time.elapsed().as_millis() as u64);
+    Ok(response)
+}
 
-    Ok(Json(response))
+#[derive(Deserialize)]
+struct LearnQuery {
+    /// Preserves the old blocking behavior for callers that need the result inline instead
+    /// of polling `GET /api/jobs/:id`.
+    sync: Option<bool>,
 }
 
+/// Enqueues a `LearnFromCode` job instead of blocking on LightRAG/Memory Bank writes, so slow
+/// upstream indexing can't time out the request; poll `GET /api/jobs/:id` for the outcome, or
+/// pass `?sync=true` to block on it inline.
 async fn learn_from_code(
     State(state): State<AppState>,
+    Query(query): Query<LearnQuery>,
     Json(request): Json<LearnRequest>,
-) -> Result<Json<LearnResponse>, StatusCode> {
+) -> Response {
+    if let Err(err) = validate_code_request(&state.config.limits, &request.file_path, &request.code, &request.language) {
+        return (err.status_code(), Json(ErrorResponse::from(err))).into_response();
+    }
+
+    // A learn event can change what's correct to suggest for this file and what search results
+    // are relevant, so drop anything cached for it. `suggest:` keys are scoped to the file path,
+    // but `search:` keys are keyed purely by query text with no link back to a file, so there's
+    // no way to invalidate just the affected ones - the whole `search:` prefix is wiped instead.
+    state.cache.invalidate_prefix(&format!("suggest:{}:", request.file_path)).await;
+    state.cache.invalidate_prefix("search:").await;
+
     let start_time = Instant::now();
 
-    // Learn from code using RAG service
-    match timeout(
-        Duration::from_secs(20),
-        state.rag_service.learn_from_code(&request)
-    ).await {
-        Ok(Ok(_)) => {
-            info!("Successfully learned from code: {}", request.file_path);
-            Ok(Json(LearnResponse {
-                message: "Code learned successfully".to_string(),
-                status: "success".to_string(),
-                processing_time_ms: start_time.elapsed().as_millis() as u64,
-            }))
-        }
-        Ok(Err(e)) => {
-            error!("Learn error: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-        Err(_) => {
-            error!("Learn timeout");
-            Err(StatusCode::REQUEST_TIMEOUT)
-        }
+    if query.sync.unwrap_or(false) {
+        let outcome = timeout(Duration::from_secs(30), state.rag_service.learn_from_code(&request)).await;
+        state.metrics.record_route(Route::Learn, start_time.elapsed().as_millis() as u64);
+        let processing_time_ms = start_time.elapsed().as_millis() as u64;
+        return match outcome {
+            Ok(Ok(_)) => (StatusCode::OK, Json(LearnResponse {
+                message: "learned".to_string(),
+                status: "completed".to_string(),
+                processing_time_ms,
+            })).into_response(),
+            Ok(Err(e)) => {
+                error!("Learn error: {}", e);
+                (e.status_code(), Json(LearnResponse {
+                    message: e.to_string(),
+                    status: "failed".to_string(),
+                    processing_time_ms,
+                })).into_response()
+            }
+            Err(_) => {
+                error!("Learn timeout");
+                (StatusCode::REQUEST_TIMEOUT, Json(LearnResponse {
+                    message: "timed out".to_string(),
+                    status: "timeout".to_string(),
+                    processing_time_ms,
+                })).into_response()
+            }
+        };
+    }
+
+    let job_id = state
+        .jobs
+        .enqueue(JobKind::LearnFromCode {
+            file_path: request.file_path.clone(),
+            code: request.code,
+            language: request.language,
+        })
+        .await;
+
+    info!("Enqueued learn job {} for {}", job_id, request.file_path);
+    state.metrics.record_route(Route::Learn, start_time.elapsed().as_millis() as u64);
+    (StatusCode::ACCEPTED, Json(JobAccepted { job_id })).into_response()
+}
+
+async fn get_job_status(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<jobs::JobRecord>, StatusCode> {
+    state.jobs.status(&job_id).await.map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn list_workers(State(state): State<AppState>) -> Json<Vec<WorkerStatus>> {
+    Json(state.workers.status().await)
+}
+
+async fn control_worker(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(command): Json<WorkerCommand>,
+) -> StatusCode {
+    let applied = match command {
+        WorkerCommand::Pause => state.workers.pause(&name),
+        WorkerCommand::Resume => state.workers.resume(&name),
+        WorkerCommand::Trigger => state.workers.trigger(&name),
+        WorkerCommand::SetInterval { interval_ms } => state.workers.set_interval(&name, interval_ms),
+    };
+    if applied {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
     }
 }
 
 async fn explain_code(
     State(state): State<AppState>,
     Json(request): Json<CodeContextRequest>,
-) -> Result<Json<ExplanationResponse>, StatusCode> {
+) -> Response {
+    if let Err(err) = validate_code_request(&state.config.limits, &request.file_path, &request.code, &request.language) {
+        return (err.status_code(), Json(ErrorResponse::from(err))).into_response();
+    }
+    match explain_impl(&state, request).await {
+        Ok(response) => Json(response).into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
+/// Shared by the `/api/explain` handler and `/api/batch`'s `explain` operation.
+async fn explain_impl(state: &AppState, request: CodeContextRequest) -> Result<ExplanationResponse, StatusCode> {
     let start_time = Instant::now();
+    let dedup_key = format!("explain:{}:{}", request.file_path, request.code.len());
 
-    // Explain code using RAG service
-    let explanation = match timeout(
-        Duration::from_secs(20),
-        state.rag_service.explain_code(&request, &None)
-    ).await {
-        Ok(Ok(explanation)) => explanation,
-        Ok(Err(e)) => {
-            error!("Code explanation error: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-        Err(_) => {
-            error!("Explanation timeout");
-            return Err(StatusCode::REQUEST_TIMEOUT);
-        }
-    };
+    // Explain code using RAG service, coalescing concurrent requests that share a key so only
+    // one of them actually calls LightRAG.
+    let explanation = state
+        .explain_inflight
+        .dedup(dedup_key, || async {
+            match timeout(Duration::from_secs(20), state.rag_service.explain_code(&request, &None)).await {
+                Ok(Ok(explanation)) => {
+                    state.metrics.record_upstream_request(Upstream::LightRag, false);
+                    Ok(explanation)
+                }
+                Ok(Err(e)) => {
+                    error!("Code explanation error: {}", e);
+                    state.metrics.record_upstream_request(Upstream::LightRag, true);
+                    Err(e.status_code())
+                }
+                Err(_) => {
+                    error!("Explanation timeout");
+                    state.metrics.record_upstream_request(Upstream::LightRag, true);
+                    state.metrics.record_upstream_timeout();
+                    Err(StatusCode::REQUEST_TIMEOUT)
+                }
+            }
+        })
+        .await?;
 
-    Ok(Json(ExplanationResponse {
+    state.metrics.record_route(Route::Explain, start_time.elapsed().as_millis() as u64);
+    Ok(ExplanationResponse {
         explanation: explanation.get("explanation").unwrap_or(&"No explanation available".to_string()).clone(),
         methodology: Some("General Development".to_string()),
         spec_kit_integration: Some("basic".to_string()),
         processing_time_ms: start_time.elapsed().as_millis() as u64,
-    }))
+    })
+}
+
+/// SSE variant of `explain_code` for editors that want the explanation to render incrementally.
+/// Unlike `suggest_code_stream`, `/api/explain` has no cache entry to fall back to or populate,
+/// so this just proxies LightRAG's chunked response straight through.
+async fn explain_code_stream(
+    State(state): State<AppState>,
+    Json(request): Json<CodeContextRequest>,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let start_time = Instant::now();
+
+    let events = async_stream::stream! {
+        let stream = match state.rag_service.explain_code_stream(&request, &None).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("RAG explanation streaming error: {}", e);
+                yield Ok(Event::default().event("error").data(e.to_string()));
+                return;
+            }
+        };
+        tokio::pin!(stream);
+
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(fragment) => {
+                    yield Ok(Event::default().event("fragment").json_data(fragment).unwrap_or_default());
+                }
+                Err(e) => {
+                    error!("RAG explanation stream item error: {}", e);
+                    yield Ok(Event::default().event("error").data(e.to_string()));
+                }
+            }
+        }
+
+        let done = ExplanationStreamDone {
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        };
+        yield Ok(Event::default().event("done").json_data(done).unwrap_or_default());
+    };
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Fans a mixed batch of suggest/search/explain operations out to `futures::future::join_all`,
+/// bounded by `RAGConfig.batch_concurrency`, reusing each operation's normal cache lookup and
+/// timeout. One failing item reports its own error instead of failing the whole batch.
+async fn batch_handler(
+    State(state): State<AppState>,
+    Json(request): Json<BatchRequest>,
+) -> Json<BatchResponse> {
+    let start_time = Instant::now();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(state.config.rag.batch_concurrency.max(1)));
+
+    let tasks = request.operations.into_iter().map(|operation| {
+        let state = state.clone();
+        let semaphore = semaphore.clone();
+        async move {
+            let _permit = semaphore.acquire_owned().await;
+            run_batch_operation(&state, operation).await
+        }
+    });
+
+    let results = futures::future::join_all(tasks).await;
+
+    Json(BatchResponse {
+        results,
+        batch_processing_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+async fn run_batch_operation(state: &AppState, operation: BatchOperation) -> BatchItemResult {
+    let item_start = Instant::now();
+    let (op, outcome) = match operation {
+        BatchOperation::Suggest(request) => (
+            "suggest",
+            match validate_code_request(&state.config.limits, &request.file_path, &request.code, &request.language) {
+                Err(err) => Err(err.to_string()),
+                Ok(()) => suggest_impl(state, request)
+                    .await
+                    .map(|r| serde_json::to_value(r).unwrap_or_default())
+                    .map_err(|status| status.to_string()),
+            },
+        ),
+        BatchOperation::Search(request) => (
+            "search",
+            match validate_search_request(&state.config.limits, &request.query) {
+                Err(err) => Err(err.to_string()),
+                Ok(()) => search_impl(state, request)
+                    .await
+                    .map(|r| serde_json::to_value(r).unwrap_or_default())
+                    .map_err(|status| status.to_string()),
+            },
+        ),
+        BatchOperation::Explain(request) => (
+            "explain",
+            match validate_code_request(&state.config.limits, &request.file_path, &request.code, &request.language) {
+                Err(err) => Err(err.to_string()),
+                Ok(()) => explain_impl(state, request)
+                    .await
+                    .map(|r| serde_json::to_value(r).unwrap_or_default())
+                    .map_err(|status| status.to_string()),
+            },
+        ),
+    };
+
+    let processing_time_ms = item_start.elapsed().as_millis() as u64;
+    match outcome {
+        Ok(result) => BatchItemResult {
+            op: op.to_string(),
+            success: true,
+            result: Some(result),
+            error: None,
+            processing_time_ms,
+        },
+        Err(error) => BatchItemResult {
+            op: op.to_string(),
+            success: false,
+            result: None,
+            error: Some(error),
+            processing_time_ms,
+        },
+    }
 }
@@ -1,10 +1,10 @@
 // use crate::config::Config; // Unused for now
+use crate::embeddings::{EmbeddingIndex, DEFAULT_RESULT_COUNT};
 use crate::error::RAGProxyError;
+use crate::store::{FsStore, MemoryBankStore, S3Store};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tokio::fs;
-use tokio::io::AsyncWriteExt;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryBankStatus {
@@ -20,100 +20,102 @@ pub struct MemoryBankContext {
     pub last_updated: String,
 }
 
-pub struct MemoryBankClient {
-    memory_bank_path: PathBuf,
+/// Generic over `MemoryBankStore` so the same logic works against a local directory
+/// ([`FsStore`]) or a shared object store ([`S3Store`]) without behavior changes.
+pub struct MemoryBankClient<S: MemoryBankStore = FsStore> {
+    store: S,
+    /// Only populated for `FsStore`: the embedding index still reads Memory Bank markdown
+    /// straight off disk, so semantic retrieval is unavailable when backed by `S3Store` until
+    /// the index itself is ported onto `MemoryBankStore`.
+    embeddings: Option<EmbeddingIndex>,
 }
 
-impl MemoryBankClient {
+impl MemoryBankClient<FsStore> {
     pub fn new(memory_bank_path: &str) -> Result<Self, RAGProxyError> {
         let path = PathBuf::from(memory_bank_path);
         Ok(Self {
-            memory_bank_path: path,
+            embeddings: Some(EmbeddingIndex::new(path.clone())),
+            store: FsStore::new(path),
         })
     }
+}
+
+impl MemoryBankClient<S3Store> {
+    pub async fn new_s3(bucket: String, prefix: String) -> Self {
+        Self {
+            store: S3Store::from_env(bucket, prefix).await,
+            embeddings: None,
+        }
+    }
+}
 
+impl<S: MemoryBankStore> MemoryBankClient<S> {
     pub async fn is_initialized(&self) -> Result<bool, RAGProxyError> {
         let essential_files = ["tasks.md", "activeContext.md", "progress.md"];
-        
+
         for file in essential_files.iter() {
-            let file_path = self.memory_bank_path.join(file);
-            if !file_path.exists() {
+            if !self.store.exists(file).await? {
                 return Ok(false);
             }
         }
-        
+
         Ok(true)
     }
 
     pub async fn initialize(&self) -> Result<(), RAGProxyError> {
-        // Create main directory
-        fs::create_dir_all(&self.memory_bank_path).await?;
-        
         // Create subdirectories
         let subdirs = ["creative", "reflection", "archive"];
         for subdir in subdirs.iter() {
-            let subdir_path = self.memory_bank_path.join(subdir);
-            fs::create_dir_all(&subdir_path).await?;
+            self.store.create_dir(subdir).await?;
         }
-        
+
         // Create essential files
         self.create_essential_files().await?;
-        
+
         Ok(())
     }
 
     async fn create_essential_files(&self) -> Result<(), RAGProxyError> {
-        // Create tasks.md
-        let tasks_file = self.memory_bank_path.join("tasks.md");
-        if !tasks_file.exists() {
-            let tasks_content = self.get_tasks_template();
-            fs::write(&tasks_file, tasks_content).await?;
+        if !self.store.exists("tasks.md").await? {
+            self.store.write("tasks.md", &self.get_tasks_template()).await?;
         }
-        
-        // Create activeContext.md
-        let context_file = self.memory_bank_path.join("activeContext.md");
-        if !context_file.exists() {
-            let context_content = self.get_context_template();
-            fs::write(&context_file, context_content).await?;
+
+        if !self.store.exists("activeContext.md").await? {
+            self.store.write("activeContext.md", &self.get_context_template()).await?;
         }
-        
-        // Create progress.md
-        let progress_file = self.memory_bank_path.join("progress.md");
-        if !progress_file.exists() {
-            let progress_content = self.get_progress_template();
-            fs::write(&progress_file, progress_content).await?;
+
+        if !self.store.exists("progress.md").await? {
+            self.store.write("progress.md", &self.get_progress_template()).await?;
         }
-        
+
         Ok(())
     }
 
     pub async fn get_status(&self) -> Result<MemoryBankStatus, RAGProxyError> {
         let mut issues = Vec::new();
         let mut initialized = true;
-        
+
         // Check essential files
         let essential_files = ["tasks.md", "activeContext.md", "progress.md"];
         for file in essential_files.iter() {
-            let file_path = self.memory_bank_path.join(file);
-            if !file_path.exists() {
+            if !self.store.exists(file).await? {
                 issues.push(format!("Missing essential file: {}", file));
                 initialized = false;
             }
         }
-        
+
         // Check subdirectories
         let subdirs = ["creative", "reflection", "archive"];
         for subdir in subdirs.iter() {
-            let subdir_path = self.memory_bank_path.join(subdir);
-            if !subdir_path.exists() {
+            if !self.store.exists(subdir).await? {
                 issues.push(format!("Missing subdirectory: {}", subdir));
                 initialized = false;
             }
         }
-        
+
         // Get current mode
         let current_mode = self.get_current_mode().await.unwrap_or_else(|_| "unknown".to_string());
-        
+
         Ok(MemoryBankStatus {
             initialized,
             current_mode,
@@ -122,17 +124,15 @@ impl MemoryBankClient {
     }
 
     pub async fn get_context(&self) -> Result<HashMap<String, String>, RAGProxyError> {
-        let context_file = self.memory_bank_path.join("activeContext.md");
-        
-        if !context_file.exists() {
+        if !self.store.exists("activeContext.md").await? {
             return Ok(HashMap::from([
                 ("current_mode".to_string(), "unknown".to_string()),
             ]));
         }
-        
-        let content = fs::read_to_string(&context_file).await?;
+
+        let content = self.store.read("activeContext.md").await?;
         let mut context = HashMap::new();
-        
+
         // Parse activeContext.md for current context
         for line in content.lines() {
             if line.starts_with("**Mode**:") {
@@ -146,26 +146,24 @@ impl MemoryBankClient {
                 context.insert("current_focus".to_string(), focus_part.to_string());
             }
         }
-        
+
         if !context.contains_key("current_mode") {
             context.insert("current_mode".to_string(), "unknown".to_string());
         }
-        
+
         Ok(context)
     }
 
     pub async fn update_context(&self, context_data: HashMap<String, serde_json::Value>) -> Result<(), RAGProxyError> {
-        let context_file = self.memory_bank_path.join("activeContext.md");
-        
         // Read existing context
-        let existing_content = if context_file.exists() {
-            fs::read_to_string(&context_file).await?
+        let existing_content = if self.store.exists("activeContext.md").await? {
+            self.store.read("activeContext.md").await?
         } else {
             self.get_context_template()
         };
-        
+
         let mut updated_content = existing_content;
-        
+
         // Update context information
         for (key, value) in context_data {
             if let Some(str_value) = value.as_str() {
@@ -186,10 +184,10 @@ impl MemoryBankClient {
                 }
             }
         }
-        
+
         // Write updated content
-        fs::write(&context_file, updated_content).await?;
-        
+        self.store.write("activeContext.md", &updated_content).await?;
+
         Ok(())
     }
 
@@ -220,7 +218,7 @@ impl MemoryBankClient {
     pub async fn integrate_rag_context(&self, spec_type: &str, code: &str) -> Result<String, RAGProxyError> {
         // Get current context
         let context = self.get_context().await?;
-        
+
         // Create RAG context based on Spec Kit methodology
         let rag_context = format!(
             "Vibecode Spec Kit Context:\n- Current Mode: {}\n- Spec Type: {}\n- Memory Bank Status: {}\n\nCode Context:\n{}\n\nIntegration Points:\n- Spec-driven development methodology\n- Memory-first principle\n- Constitutional AI approach",
@@ -229,42 +227,30 @@ impl MemoryBankClient {
             self.get_status().await.map(|s| s.initialized).unwrap_or(false),
             code
         );
-        
+
         // Store in memory bank for future reference
-        let rag_file = self.memory_bank_path.join("rag_context.md");
-        let mut file = fs::OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&rag_file)
-            .await?;
-        
         let content = format!("# RAG Integration Context\n\n{}", rag_context);
-        file.write_all(content.as_bytes()).await?;
-        
+        self.store.write("rag_context.md", &content).await?;
+
         Ok("Memory Bank integrated with RAG context".to_string())
     }
 
+    /// Retrieves the Memory Bank chunks most semantically relevant to `query` via the
+    /// embedding index (see [`EmbeddingIndex`]), rather than lexical substring matching, so a
+    /// query like "how do we handle auth errors" can surface notes with no word overlap.
+    /// Falls back to `activeContext.md` verbatim when no embedding index is available (e.g.
+    /// under `S3Store`) or the index has nothing yet.
     pub async fn get_rag_context(&self, query: &str) -> Result<Option<String>, RAGProxyError> {
-        // Read RAG context file
-        let rag_file = self.memory_bank_path.join("rag_context.md");
-        
-        if rag_file.exists() {
-            let content = fs::read_to_string(&rag_file).await?;
-            
-            // Simple query matching (could be enhanced with semantic search)
-            if content.to_lowercase().contains(&query.to_lowercase()) {
-                return Ok(Some(content));
+        if let Some(embeddings) = &self.embeddings {
+            if let Some(context) = embeddings.query(query, DEFAULT_RESULT_COUNT).await? {
+                return Ok(Some(context));
             }
         }
-        
-        // Fallback to activeContext.md
-        let context_file = self.memory_bank_path.join("activeContext.md");
-        if context_file.exists() {
-            let content = fs::read_to_string(&context_file).await?;
-            return Ok(Some(content));
+
+        if self.store.exists("activeContext.md").await? {
+            return Ok(Some(self.store.read("activeContext.md").await?));
         }
-        
+
         Ok(None)
     }
 
@@ -275,8 +261,8 @@ impl MemoryBankClient {
     fn get_tasks_template(&self) -> String {
         r#"# Memory Bank Tasks
 
-**Status**: ACTIVE  
-**Last Updated**: [DATE]  
+**Status**: ACTIVE
+**Last Updated**: [DATE]
 **Current Phase**: [PHASE]
 
 ## Current Task
@@ -297,8 +283,8 @@ impl MemoryBankClient {
     fn get_context_template(&self) -> String {
         r#"# Active Context
 
-**Session**: [Session Name]  
-**Date**: [DATE]  
+**Session**: [Session Name]
+**Date**: [DATE]
 **Current Focus**: [Focus Area]
 
 ## Current Context
@@ -320,8 +306,8 @@ impl MemoryBankClient {
     fn get_progress_template(&self) -> String {
         r#"# Progress Tracking
 
-**Project**: [Project Name]  
-**Start Date**: [DATE]  
+**Project**: [Project Name]
+**Start Date**: [DATE]
 **Current Phase**: [Current Phase]
 
 ## Phase Status
@@ -0,0 +1,263 @@
+use crate::cache::CacheStats;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+/// Latency bucket upper bounds in milliseconds, matching Prometheus's cumulative histogram
+/// convention (`le` = "less than or equal to").
+const BUCKETS_MS: [u64; 9] = [5, 10, 25, 50, 100, 250, 500, 1000, 5000];
+
+#[derive(Default)]
+struct Counter(AtomicU64);
+
+impl Counter {
+    fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [AtomicU64; BUCKETS_MS.len()],
+    sum_ms: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, elapsed_ms: u64) {
+        for (bound, bucket) in BUCKETS_MS.iter().zip(self.buckets.iter()) {
+            if elapsed_ms <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.sum_ms.fetch_add(elapsed_ms, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        for (bound, bucket) in BUCKETS_MS.iter().zip(self.buckets.iter()) {
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels}le=\"{bound}\"}} {}",
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}le=\"+Inf\"}} {}",
+            self.count.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "{name}_sum{{{}}} {}",
+            labels.trim_end_matches(','),
+            self.sum_ms.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "{name}_count{{{}}} {}",
+            labels.trim_end_matches(','),
+            self.count.load(Ordering::Relaxed)
+        );
+    }
+}
+
+#[derive(Default)]
+struct RouteMetrics {
+    requests: Counter,
+    latency: Histogram,
+}
+
+/// Prometheus-style counters and histograms for the RAG proxy, gated behind
+/// `CacheConfig.enable_metrics`. Handlers call `record_route` after each request and
+/// `record_cache_hit`/`record_cache_miss` around their cache lookup; `render` produces the
+/// full text-exposition payload served at `/metrics`.
+#[derive(Default)]
+pub struct Metrics {
+    suggest: RouteMetrics,
+    search: RouteMetrics,
+    learn: RouteMetrics,
+    explain: RouteMetrics,
+    cache_hits: Counter,
+    cache_misses: Counter,
+    cache_size: AtomicI64,
+    lightrag_requests: Counter,
+    lightrag_failures: Counter,
+    n8n_requests: Counter,
+    n8n_failures: Counter,
+    supabase_requests: Counter,
+    supabase_failures: Counter,
+    upstream_timeouts: Counter,
+    memory_bank_integration_failures: Counter,
+}
+
+pub enum Route {
+    Suggest,
+    Search,
+    Learn,
+    Explain,
+}
+
+pub enum Upstream {
+    LightRag,
+    N8n,
+    Supabase,
+}
+
+impl Metrics {
+    fn route(&self, route: &Route) -> &RouteMetrics {
+        match route {
+            Route::Suggest => &self.suggest,
+            Route::Search => &self.search,
+            Route::Learn => &self.learn,
+            Route::Explain => &self.explain,
+        }
+    }
+
+    pub fn record_route(&self, route: Route, elapsed_ms: u64) {
+        let metrics = self.route(&route);
+        metrics.requests.inc();
+        metrics.latency.observe(elapsed_ms);
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.inc();
+    }
+
+    pub fn record_cache_miss(&self) {
+        self.cache_misses.inc();
+    }
+
+    /// Fallback size gauge for backends that can't report `CacheBackend::stats()` (no uniform
+    /// way to enumerate entries behind `Arc<dyn CacheBackend>`). `render()` prefers the accurate
+    /// `CacheStats.size` whenever a backend provides one, since this counter only ever grows -
+    /// callers bump it on a cache-miss write but nothing ever decrements it on invalidate/evict.
+    pub fn adjust_cache_size(&self, delta: i64) {
+        self.cache_size.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    pub fn record_upstream_request(&self, upstream: Upstream, failed: bool) {
+        let (requests, failures) = match upstream {
+            Upstream::LightRag => (&self.lightrag_requests, &self.lightrag_failures),
+            Upstream::N8n => (&self.n8n_requests, &self.n8n_failures),
+            Upstream::Supabase => (&self.supabase_requests, &self.supabase_failures),
+        };
+        requests.inc();
+        if failed {
+            failures.inc();
+        }
+    }
+
+    /// Distinguishes a `timeout(...)` expiring from the backend call itself returning an
+    /// error; call alongside `record_upstream_request(upstream, true)` in the `Err(_)` arm of
+    /// a handler's `timeout(...)` match.
+    pub fn record_upstream_timeout(&self) {
+        self.upstream_timeouts.inc();
+    }
+
+    pub fn record_memory_bank_integration_failure(&self) {
+        self.memory_bank_integration_failures.inc();
+    }
+
+    /// Renders the full text-exposition payload served at `/metrics`. `cache_stats`, when the
+    /// active `CacheBackend` can report one (see `CacheBackend::stats`), adds the
+    /// `cache_evictions_total`/`cache_memory_bytes` series straight from `CacheStats` rather
+    /// than tracking separate atomics for numbers the backend already counts itself.
+    pub fn render(&self, cache_stats: Option<&CacheStats>) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP rag_proxy_requests_total Requests handled per route.");
+        let _ = writeln!(out, "# TYPE rag_proxy_requests_total counter");
+        for (route, metrics) in [
+            ("suggest", &self.suggest),
+            ("search", &self.search),
+            ("learn", &self.learn),
+            ("explain", &self.explain),
+        ] {
+            let _ = writeln!(
+                out,
+                "rag_proxy_requests_total{{route=\"{route}\"}} {}",
+                metrics.requests.get()
+            );
+        }
+
+        let _ = writeln!(out, "# HELP rag_proxy_request_duration_ms Handler latency in milliseconds.");
+        let _ = writeln!(out, "# TYPE rag_proxy_request_duration_ms histogram");
+        for (route, metrics) in [
+            ("suggest", &self.suggest),
+            ("search", &self.search),
+            ("learn", &self.learn),
+            ("explain", &self.explain),
+        ] {
+            metrics.latency.render(
+                &mut out,
+                "rag_proxy_request_duration_ms",
+                &format!("route=\"{route}\","),
+            );
+        }
+
+        let _ = writeln!(out, "# HELP rag_proxy_cache_hits_total Cache hits.");
+        let _ = writeln!(out, "# TYPE rag_proxy_cache_hits_total counter");
+        let _ = writeln!(out, "rag_proxy_cache_hits_total {}", self.cache_hits.get());
+        let _ = writeln!(out, "# HELP rag_proxy_cache_misses_total Cache misses.");
+        let _ = writeln!(out, "# TYPE rag_proxy_cache_misses_total counter");
+        let _ = writeln!(out, "rag_proxy_cache_misses_total {}", self.cache_misses.get());
+        let _ = writeln!(out, "# HELP rag_proxy_cache_size Approximate number of live cache entries.");
+        let _ = writeln!(out, "# TYPE rag_proxy_cache_size gauge");
+        // `cache_stats.size` is the backend's own live count (`CacheManager::get_stats` ->
+        // `cache.len()`) and accounts for invalidation/eviction/expiry; `self.cache_size` only
+        // ever gets incremented (see `adjust_cache_size`'s callers), so it's just a fallback for
+        // backends that don't report `CacheStats` at all.
+        let cache_size = cache_stats
+            .map(|stats| stats.size as i64)
+            .unwrap_or_else(|| self.cache_size.load(Ordering::Relaxed).max(0));
+        let _ = writeln!(out, "rag_proxy_cache_size {}", cache_size);
+
+        if let Some(stats) = cache_stats {
+            let _ = writeln!(out, "# HELP rag_proxy_cache_evictions_total Entries evicted from the active cache backend.");
+            let _ = writeln!(out, "# TYPE rag_proxy_cache_evictions_total counter");
+            let _ = writeln!(out, "rag_proxy_cache_evictions_total {}", stats.evictions);
+            let _ = writeln!(out, "# HELP rag_proxy_cache_memory_bytes Approximate memory used by cached entries.");
+            let _ = writeln!(out, "# TYPE rag_proxy_cache_memory_bytes gauge");
+            let _ = writeln!(out, "rag_proxy_cache_memory_bytes {}", stats.memory_usage_bytes);
+        }
+
+        let _ = writeln!(out, "# HELP rag_proxy_upstream_requests_total Requests sent to upstream services.");
+        let _ = writeln!(out, "# TYPE rag_proxy_upstream_requests_total counter");
+        let _ = writeln!(out, "# HELP rag_proxy_upstream_failures_total Failed requests to upstream services.");
+        let _ = writeln!(out, "# TYPE rag_proxy_upstream_failures_total counter");
+        for (service, requests, failures) in [
+            ("lightrag", &self.lightrag_requests, &self.lightrag_failures),
+            ("n8n", &self.n8n_requests, &self.n8n_failures),
+            ("supabase", &self.supabase_requests, &self.supabase_failures),
+        ] {
+            let _ = writeln!(
+                out,
+                "rag_proxy_upstream_requests_total{{service=\"{service}\"}} {}",
+                requests.get()
+            );
+            let _ = writeln!(
+                out,
+                "rag_proxy_upstream_failures_total{{service=\"{service}\"}} {}",
+                failures.get()
+            );
+        }
+
+        let _ = writeln!(out, "# HELP rag_proxy_upstream_timeouts_total Upstream calls that hit their timeout deadline.");
+        let _ = writeln!(out, "# TYPE rag_proxy_upstream_timeouts_total counter");
+        let _ = writeln!(out, "rag_proxy_upstream_timeouts_total {}", self.upstream_timeouts.get());
+
+        let _ = writeln!(out, "# HELP rag_proxy_memory_bank_integration_failures_total Failed Memory Bank integration jobs.");
+        let _ = writeln!(out, "# TYPE rag_proxy_memory_bank_integration_failures_total counter");
+        let _ = writeln!(
+            out,
+            "rag_proxy_memory_bank_integration_failures_total {}",
+            self.memory_bank_integration_failures.get()
+        );
+
+        out
+    }
+}
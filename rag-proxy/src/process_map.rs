@@ -0,0 +1,63 @@
+use axum::http::StatusCode;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Collapses concurrent callers sharing the same key into a single in-flight computation.
+/// The first caller for a key becomes the "leader" that runs `compute` and broadcasts the
+/// result; every other caller for that key awaits the leader's broadcast instead of
+/// redoing the same expensive LightRAG/Memory Bank work.
+pub struct ProcessMap<T: Clone + Send + Sync + 'static> {
+    inflight: DashMap<String, broadcast::Sender<Arc<Result<T, StatusCode>>>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> ProcessMap<T> {
+    pub fn new() -> Self {
+        Self {
+            inflight: DashMap::new(),
+        }
+    }
+
+    pub async fn dedup<F, Fut>(&self, key: String, compute: F) -> Result<T, StatusCode>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, StatusCode>>,
+    {
+        enum Role<T: Clone + Send + Sync + 'static> {
+            Leader(broadcast::Sender<Arc<Result<T, StatusCode>>>),
+            Follower(broadcast::Receiver<Arc<Result<T, StatusCode>>>),
+        }
+
+        let role = match self.inflight.entry(key.clone()) {
+            Entry::Occupied(entry) => Role::Follower(entry.get().subscribe()),
+            Entry::Vacant(entry) => {
+                let (sender, _receiver) = broadcast::channel(16);
+                entry.insert(sender.clone());
+                Role::Leader(sender)
+            }
+        };
+
+        match role {
+            Role::Leader(sender) => {
+                let result = compute().await;
+                // Remove before broadcasting (and on error too) so a failed leader doesn't
+                // poison the map for the next request with this key.
+                self.inflight.remove(&key);
+                let _ = sender.send(Arc::new(result.clone()));
+                result
+            }
+            Role::Follower(mut receiver) => match receiver.recv().await {
+                Ok(result) => (*result).clone(),
+                Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+            },
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for ProcessMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,10 +1,89 @@
-use crate::config::Config;
+use crate::config::{Config, LightRagAuth, RetryConfig};
 use crate::error::RAGProxyError;
 use crate::types::*;
+use async_trait::async_trait;
+use rand::Rng;
 use std::collections::HashMap;
-use reqwest::Client;
+use std::sync::Arc;
+use reqwest::{header, Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// A short hex identifier attached to every outbound LightRAG request (as `X-Request-Id`) and
+/// recorded on the request's tracing span, so proxy and LightRAG logs can be correlated.
+fn generate_request_id() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Retries `f` on errors classified as `is_retryable()` (connection failures, timeouts, 5xx)
+/// up to `retry.max_retries` additional times, using exponential backoff with jitter:
+/// `delay = min(base_delay_ms * 2^attempt, cap_delay_ms)`, then add a random jitter in
+/// `[0, delay/2]`. If the error itself suggests a `retry_after_seconds()` (e.g. a rate limit),
+/// that value is used as a floor so we never sleep less than the upstream asked for. Errors
+/// that aren't retryable (e.g. a 4xx `ValidationError`) are returned immediately.
+async fn retry_request<F, Fut, T>(retry: &RetryConfig, mut f: F) -> Result<T, RAGProxyError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, RAGProxyError>>,
+{
+    let base_delay = Duration::from_millis(retry.base_delay_ms);
+    let cap_delay = Duration::from_millis(retry.cap_delay_ms);
+
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => {
+                if attempt > 0 {
+                    tracing::info!(retries = attempt, "LightRAG request succeeded after retrying");
+                }
+                return Ok(value);
+            }
+            Err(err) if attempt < retry.max_retries && err.is_retryable() => {
+                let delay = base_delay.saturating_mul(1 << attempt.min(31)).min(cap_delay);
+                let jitter = Duration::from_millis(
+                    rand::thread_rng().gen_range(0..=(delay.as_millis() as u64 / 2)),
+                );
+                let mut sleep_for = delay + jitter;
+                if let Some(floor_secs) = err.retry_after_seconds() {
+                    sleep_for = sleep_for.max(Duration::from_secs(floor_secs));
+                }
+                tracing::warn!(
+                    attempt = attempt + 1,
+                    delay_ms = sleep_for.as_millis() as u64,
+                    error = %err,
+                    "retrying LightRAG request after retryable error"
+                );
+                tokio::time::sleep(sleep_for).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Classifies a non-2xx LightRAG response: 401/403 means our credentials were rejected, 4xx
+/// otherwise means the proxy sent a bad request (neither is worth retrying), anything else
+/// means the upstream itself is unwell.
+fn classify_http_error(status: reqwest::StatusCode, context: &str) -> RAGProxyError {
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        RAGProxyError::AuthenticationError(format!("{}: {}", context, status))
+    } else if status.is_client_error() {
+        RAGProxyError::ValidationError(format!("{}: {}", context, status))
+    } else {
+        RAGProxyError::ServiceError(format!("{}: {}", context, status))
+    }
+}
+
+/// Refreshes an expired Bearer token. Invoked by `LightRagHttpBackend` on a 401/403 when
+/// `config.lightrag.auth` is `Bearer`, before retrying the request exactly once with the new
+/// token.
+#[async_trait]
+pub trait TokenRefresher: Send + Sync {
+    async fn refresh(&self) -> Result<String, RAGProxyError>;
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LightRAGHealth {
@@ -20,48 +99,389 @@ pub struct LightRAGResponse {
     pub error: Option<String>,
 }
 
-pub struct RAGService {
+/// Transport abstraction for whatever RAG engine actually answers these four requests.
+/// `RAGService<B>` only ever talks to `B`, so the Spec-Kit enhancement logic in `suggest_code`,
+/// `search_context`, and `explain_code` stays backend-agnostic - swap in a different engine, or
+/// an in-memory fake for unit tests, by implementing this trait instead of spinning up LightRAG.
+#[async_trait]
+pub trait RagBackend: Send + Sync {
+    async fn health(&self) -> Result<LightRAGHealth, RAGProxyError>;
+    async fn suggest(&self, request_body: serde_json::Value) -> Result<LightRAGResponse, RAGProxyError>;
+    async fn query(&self, request_body: serde_json::Value) -> Result<serde_json::Value, RAGProxyError>;
+    async fn insert_code(&self, request_body: serde_json::Value) -> Result<(), RAGProxyError>;
+}
+
+/// The default `RagBackend`: talks to `config.lightrag.url` over plain HTTP via `reqwest`,
+/// retrying retryable failures per `config.retry` and attaching `config.lightrag.auth` to every
+/// request.
+pub struct LightRagHttpBackend {
     client: Client,
     config: Config,
+    /// Current Bearer token, refreshable at runtime; `None` unless `config.lightrag.auth` is
+    /// `Bearer`.
+    bearer_token: Option<RwLock<String>>,
+    token_refresher: Option<Arc<dyn TokenRefresher>>,
 }
 
-impl RAGService {
-    pub async fn new(config: &Config) -> Result<Self, RAGProxyError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.rag.timeout_seconds))
-            .build()?;
+impl LightRagHttpBackend {
+    pub fn new(config: &Config) -> Result<Self, RAGProxyError> {
+        Self::build(config, None)
+    }
+
+    /// Same as `new`, but installs a `TokenRefresher` invoked on a 401/403 when the active
+    /// credential is `LightRagAuth::Bearer`, before retrying the request once with the
+    /// refreshed token.
+    pub fn with_token_refresher(
+        config: &Config,
+        refresher: Arc<dyn TokenRefresher>,
+    ) -> Result<Self, RAGProxyError> {
+        Self::build(config, Some(refresher))
+    }
+
+    fn build(config: &Config, token_refresher: Option<Arc<dyn TokenRefresher>>) -> Result<Self, RAGProxyError> {
+        let mut default_headers = header::HeaderMap::new();
+        if let LightRagAuth::ApiKey { header: name, value } = &config.lightrag.auth {
+            let header_name = header::HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                RAGProxyError::configuration_error(format!("invalid LightRAG auth header name: {e}"))
+            })?;
+            let header_value = header::HeaderValue::from_str(value.expose()).map_err(|e| {
+                RAGProxyError::configuration_error(format!("invalid LightRAG auth header value: {e}"))
+            })?;
+            default_headers.insert(header_name, header_value);
+        }
+
+        let mut client_builder = Client::builder()
+            .timeout(Duration::from_secs(config.lightrag.timeout_seconds))
+            .default_headers(default_headers)
+            .tls_built_in_root_certs(config.tls.use_native_certs);
+
+        for ca_path in &config.tls.ca_bundle_paths {
+            let pem = std::fs::read(ca_path).map_err(|e| {
+                RAGProxyError::configuration_error(format!("failed to read TLS CA bundle {ca_path}: {e}"))
+            })?;
+            let cert = reqwest::Certificate::from_pem(&pem).map_err(|e| {
+                RAGProxyError::configuration_error(format!("failed to parse TLS CA bundle {ca_path}: {e}"))
+            })?;
+            client_builder = client_builder.add_root_certificate(cert);
+        }
+
+        if let (Some(cert_path), Some(key_path)) =
+            (&config.tls.client_cert_path, &config.tls.client_key_path)
+        {
+            let mut identity_pem = std::fs::read(cert_path).map_err(|e| {
+                RAGProxyError::configuration_error(format!("failed to read TLS client cert {cert_path}: {e}"))
+            })?;
+            let key_pem = std::fs::read(key_path).map_err(|e| {
+                RAGProxyError::configuration_error(format!("failed to read TLS client key {key_path}: {e}"))
+            })?;
+            identity_pem.extend_from_slice(&key_pem);
+            let identity = reqwest::Identity::from_pem(&identity_pem).map_err(|e| {
+                RAGProxyError::configuration_error(format!("failed to build TLS client identity: {e}"))
+            })?;
+            client_builder = client_builder.identity(identity);
+        }
+
+        let client = client_builder.build()?;
+
+        let bearer_token = match &config.lightrag.auth {
+            LightRagAuth::Bearer { token } => Some(RwLock::new(token.expose().to_string())),
+            _ => None,
+        };
 
         Ok(Self {
             client,
             config: config.clone(),
+            bearer_token,
+            token_refresher,
         })
     }
 
-    pub async fn health_check(&self) -> Result<LightRAGHealth, RAGProxyError> {
+    /// Applies the active `Bearer` credential, if any; `ApiKey` is already baked into the
+    /// client's default headers at construction time.
+    async fn authorize(&self, builder: RequestBuilder) -> RequestBuilder {
+        let Some(lock) = &self.bearer_token else {
+            return builder;
+        };
+        builder.bearer_auth(lock.read().await.clone())
+    }
+
+    /// Sends the request built by `build`, applying the active credential and an `X-Request-Id`
+    /// header, and records the downstream status and latency on the calling method's tracing
+    /// span. On a 401/403 with a `TokenRefresher` installed, refreshes the Bearer token and
+    /// retries exactly once with the new token.
+    async fn send_authorized<F>(&self, build: F) -> Result<reqwest::Response, RAGProxyError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let request_id = generate_request_id();
+        tracing::Span::current().record("request_id", request_id.as_str());
+
+        let started = Instant::now();
+        let response = self
+            .authorize(build())
+            .await
+            .header("X-Request-Id", request_id.clone())
+            .send()
+            .await?;
+        tracing::info!(
+            request_id = %request_id,
+            status = %response.status(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "received LightRAG response"
+        );
+
+        if matches!(
+            response.status(),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+        ) {
+            if let (Some(lock), Some(refresher)) = (&self.bearer_token, &self.token_refresher) {
+                tracing::warn!(request_id = %request_id, "LightRAG rejected credentials, refreshing token");
+                let fresh_token = refresher.refresh().await?;
+                *lock.write().await = fresh_token;
+
+                let retry_started = Instant::now();
+                let retried = self
+                    .authorize(build())
+                    .await
+                    .header("X-Request-Id", request_id.clone())
+                    .send()
+                    .await?;
+                tracing::info!(
+                    request_id = %request_id,
+                    status = %retried.status(),
+                    latency_ms = retry_started.elapsed().as_millis() as u64,
+                    "received LightRAG response after token refresh"
+                );
+                return Ok(retried);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl RagBackend for LightRagHttpBackend {
+    async fn health(&self) -> Result<LightRAGHealth, RAGProxyError> {
         let url = format!("{}/health", self.config.lightrag.url);
-        
-        let response = self.client
-            .get(&url)
+
+        retry_request(&self.config.retry, || async {
+            let response = self.send_authorized(|| self.client.get(&url)).await?;
+
+            if response.status().is_success() {
+                let health: LightRAGHealth = response.json().await?;
+                Ok(health)
+            } else {
+                Err(classify_http_error(response.status(), "LightRAG health check failed"))
+            }
+        }).await
+    }
+
+    async fn suggest(&self, request_body: serde_json::Value) -> Result<LightRAGResponse, RAGProxyError> {
+        let url = format!("{}/suggest", self.config.lightrag.url);
+
+        retry_request(&self.config.retry, || async {
+            let response = self.send_authorized(|| self.client.post(&url).json(&request_body)).await?;
+
+            if response.status().is_success() {
+                Ok(response.json().await?)
+            } else {
+                Err(classify_http_error(response.status(), "LightRAG suggest failed"))
+            }
+        }).await
+    }
+
+    async fn query(&self, request_body: serde_json::Value) -> Result<serde_json::Value, RAGProxyError> {
+        let url = format!("{}/query", self.config.lightrag.url);
+
+        retry_request(&self.config.retry, || async {
+            let response = self.send_authorized(|| self.client.post(&url).json(&request_body)).await?;
+
+            if response.status().is_success() {
+                Ok(response.json().await?)
+            } else {
+                Err(classify_http_error(response.status(), "LightRAG query failed"))
+            }
+        }).await
+    }
+
+    async fn insert_code(&self, request_body: serde_json::Value) -> Result<(), RAGProxyError> {
+        let url = format!("{}/insert_code", self.config.lightrag.url);
+
+        retry_request(&self.config.retry, || async {
+            let response = self.send_authorized(|| self.client.post(&url).json(&request_body)).await?;
+
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(classify_http_error(response.status(), "LightRAG learning failed"))
+            }
+        }).await
+    }
+}
+
+/// Defaults to `LightRagHttpBackend` so existing callers (`RAGService::new`, `Arc<RAGService>`
+/// in `AppState`) don't need to change; pass a different `B` (e.g. an in-memory fake) via
+/// `RAGService::with_backend` to swap engines or test without a live LightRAG instance.
+pub struct RAGService<B: RagBackend = LightRagHttpBackend> {
+    backend: B,
+}
+
+impl RAGService<LightRagHttpBackend> {
+    pub async fn new(config: &Config) -> Result<Self, RAGProxyError> {
+        Ok(Self {
+            backend: LightRagHttpBackend::new(config)?,
+        })
+    }
+
+    /// Streams suggestions from LightRAG's chunked `/suggest/stream` endpoint as they
+    /// arrive, instead of waiting for the full completion. Each upstream `data:` frame
+    /// is expected to carry a single `CodeSuggestion` JSON fragment.
+    ///
+    /// Only available on the concrete HTTP backend: `RagBackend` has no streaming method yet,
+    /// so a non-default backend (e.g. a test fake) doesn't need to implement one.
+    #[tracing::instrument(
+        skip(self, request, spec_kit_context),
+        fields(
+            language = %request.language,
+            file_path = %request.file_path,
+            spec_kit_context = spec_kit_context.is_some(),
+            request_id = tracing::field::Empty,
+        )
+    )]
+    pub async fn suggest_code_stream(
+        &self,
+        request: &CodeContextRequest,
+        spec_kit_context: &Option<String>,
+    ) -> Result<impl futures::Stream<Item = Result<CodeSuggestion, RAGProxyError>>, RAGProxyError> {
+        let url = format!("{}/suggest/stream", self.backend.config.lightrag.url);
+
+        let mut request_body = serde_json::json!({
+            "context": request.code,
+            "cursor_position": {"line": 0, "character": 0},
+            "language": request.language,
+            "file_path": request.file_path
+        });
+
+        if let Some(spec_context) = spec_kit_context {
+            request_body["spec_kit_context"] = serde_json::Value::String(spec_context.clone());
+        }
+
+        let request_id = generate_request_id();
+        tracing::Span::current().record("request_id", request_id.as_str());
+
+        let response = self
+            .backend
+            .authorize(self.backend.client.post(&url).json(&request_body))
+            .await
+            .header("X-Request-Id", request_id)
             .send()
             .await?;
 
-        if response.status().is_success() {
-            let health: LightRAGHealth = response.json().await?;
-            Ok(health)
-        } else {
-            Err(RAGProxyError::ServiceUnavailable(
-                format!("LightRAG health check failed: {}", response.status())
-            ))
+        if !response.status().is_success() {
+            return Err(RAGProxyError::ServiceError(format!(
+                "LightRAG suggest/stream failed: {}",
+                response.status()
+            )));
         }
+
+        // Emit LightRAG's own suggestions as they arrive, then the Spec Kit methodology
+        // suggestion (the same one `enhance_suggestions_with_spec_kit` appends to the buffered
+        // `suggest_code` response) as one final item once the upstream stream completes.
+        use futures::StreamExt;
+        let methodology_suggestion = spec_kit_context.as_ref().map(|spec_context| CodeSuggestion {
+            text: self.generate_methodology_suggestion(&request.language, spec_context),
+            confidence: 0.9,
+            r#type: "methodology".to_string(),
+            source: Some("spec_kit".to_string()),
+        });
+
+        Ok(sse_frame_stream::<CodeSuggestion>(response)
+            .chain(futures::stream::iter(methodology_suggestion.map(Ok))))
     }
 
+    /// Streams explanation fragments from LightRAG's chunked `/query` endpoint (requested with
+    /// `"stream": true`) as they arrive, instead of waiting for the full explanation to be
+    /// assembled. Each upstream `data:` frame is expected to carry a single `ExplanationFragment`
+    /// JSON fragment.
+    #[tracing::instrument(
+        skip(self, request, spec_kit_context),
+        fields(
+            language = %request.language,
+            file_path = %request.file_path,
+            mode = "hybrid",
+            top_k = 5,
+            spec_kit_context = spec_kit_context.is_some(),
+            request_id = tracing::field::Empty,
+        )
+    )]
+    pub async fn explain_code_stream(
+        &self,
+        request: &CodeContextRequest,
+        spec_kit_context: &Option<String>,
+    ) -> Result<impl futures::Stream<Item = Result<ExplanationFragment, RAGProxyError>>, RAGProxyError> {
+        let url = format!("{}/query", self.backend.config.lightrag.url);
+
+        let explanation_query = format!("Explain this {} code:\n{}", request.language, request.code);
+        let mut request_body = serde_json::json!({
+            "query": explanation_query,
+            "mode": "hybrid",
+            "top_k": 5,
+            "stream": true
+        });
+
+        if let Some(spec_context) = spec_kit_context {
+            let enhanced_query = format!("{} [Context: {}]", explanation_query, spec_context);
+            request_body["query"] = serde_json::Value::String(enhanced_query);
+        }
+
+        let request_id = generate_request_id();
+        tracing::Span::current().record("request_id", request_id.as_str());
+
+        let response = self
+            .backend
+            .authorize(self.backend.client.post(&url).json(&request_body))
+            .await
+            .header("X-Request-Id", request_id)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(RAGProxyError::ServiceError(format!(
+                "LightRAG query/stream failed: {}",
+                response.status()
+            )));
+        }
+
+        Ok(sse_frame_stream::<ExplanationFragment>(response))
+    }
+}
+
+impl<B: RagBackend> RAGService<B> {
+    /// Builds a `RAGService` around any `RagBackend`, e.g. an in-memory fake for unit tests.
+    pub fn with_backend(backend: B) -> Self {
+        Self { backend }
+    }
+
+    #[tracing::instrument(skip(self), fields(request_id = tracing::field::Empty))]
+    pub async fn health_check(&self) -> Result<LightRAGHealth, RAGProxyError> {
+        self.backend.health().await
+    }
+
+    #[tracing::instrument(
+        skip(self, request, spec_kit_context),
+        fields(
+            language = %request.language,
+            file_path = %request.file_path,
+            spec_kit_context = spec_kit_context.is_some(),
+            request_id = tracing::field::Empty,
+        )
+    )]
     pub async fn suggest_code(
         &self,
         request: &CodeContextRequest,
         spec_kit_context: &Option<String>,
     ) -> Result<Vec<CodeSuggestion>, RAGProxyError> {
-        let url = format!("{}/suggest", self.config.lightrag.url);
-        
         let mut request_body = serde_json::json!({
             "context": request.code,
             "cursor_position": {"line": 0, "character": 0},
@@ -74,43 +494,36 @@ impl RAGService {
             request_body["spec_kit_context"] = serde_json::Value::String(spec_context.clone());
         }
 
-        let response = self.client
-            .post(&url)
-            .json(&request_body)
-            .send()
-            .await?;
+        let result = self.backend.suggest(request_body).await?;
 
-        if response.status().is_success() {
-            let result: LightRAGResponse = response.json().await?;
-            
-            if let Some(suggestions) = result.suggestions {
+        match result.suggestions {
+            Some(suggestions) => {
                 // Enhance suggestions with Spec Kit methodology
-                let enhanced_suggestions = self.enhance_suggestions_with_spec_kit(
+                Ok(self.enhance_suggestions_with_spec_kit(
                     suggestions,
                     spec_kit_context,
                     &request.language,
-                ).await;
-                
-                Ok(enhanced_suggestions)
-            } else {
-                // Fallback suggestions
-                Ok(self.generate_fallback_suggestions(&request.language))
+                ).await)
             }
-        } else {
-            Err(RAGProxyError::ServiceError(
-                format!("LightRAG suggest failed: {}", response.status())
-            ))
+            None => Ok(self.generate_fallback_suggestions(&request.language)),
         }
     }
 
+    #[tracing::instrument(
+        skip(self, query, spec_kit_context, limit),
+        fields(
+            mode = "hybrid",
+            top_k = limit,
+            spec_kit_context = spec_kit_context.is_some(),
+            request_id = tracing::field::Empty,
+        )
+    )]
     pub async fn search_context(
         &self,
         query: &str,
         spec_kit_context: &Option<String>,
         limit: usize,
     ) -> Result<Vec<SearchResult>, RAGProxyError> {
-        let url = format!("{}/query", self.config.lightrag.url);
-        
         let mut request_body = serde_json::json!({
             "query": query,
             "mode": "hybrid",
@@ -123,48 +536,42 @@ impl RAGService {
             request_body["query"] = serde_json::Value::String(enhanced_query);
         }
 
-        let response = self.client
-            .post(&url)
-            .json(&request_body)
-            .send()
-            .await?;
+        let result = self.backend.query(request_body).await?;
 
-        if response.status().is_success() {
-            let result: serde_json::Value = response.json().await?;
-            
-            // Parse LightRAG response into SearchResult format
-            let mut results = Vec::new();
-            
-            if let Some(rag_result) = result.get("result").and_then(|r| r.as_str()) {
-                // Split result into chunks and create SearchResult objects
-                let chunks: Vec<&str> = rag_result.split("\n\n").collect();
-                
-                for (i, chunk) in chunks.iter().enumerate().take(limit) {
-                    if !chunk.trim().is_empty() {
-                        results.push(SearchResult {
-                            content: chunk.to_string(),
-                            relevance: 1.0 - (i as f64 * 0.1), // Decreasing relevance
-                            source: "lightrag".to_string(),
-                            metadata: HashMap::from([
-                                ("spec_kit_enriched".to_string(), spec_kit_context.is_some().to_string()),
-                                ("chunk_index".to_string(), i.to_string()),
-                            ]),
-                        });
-                    }
+        // Parse LightRAG response into SearchResult format
+        let mut results = Vec::new();
+
+        if let Some(rag_result) = result.get("result").and_then(|r| r.as_str()) {
+            // Split result into chunks and create SearchResult objects
+            let chunks: Vec<&str> = rag_result.split("\n\n").collect();
+
+            for (i, chunk) in chunks.iter().enumerate().take(limit) {
+                if !chunk.trim().is_empty() {
+                    results.push(SearchResult {
+                        content: chunk.to_string(),
+                        relevance: 1.0 - (i as f64 * 0.1), // Decreasing relevance
+                        source: "lightrag".to_string(),
+                        metadata: HashMap::from([
+                            ("spec_kit_enriched".to_string(), spec_kit_context.is_some().to_string()),
+                            ("chunk_index".to_string(), i.to_string()),
+                        ]),
+                    });
                 }
             }
-            
-            Ok(results)
-        } else {
-            Err(RAGProxyError::ServiceError(
-                format!("LightRAG search failed: {}", response.status())
-            ))
         }
+
+        Ok(results)
     }
 
+    #[tracing::instrument(
+        skip(self, request),
+        fields(
+            language = %request.language,
+            file_path = %request.file_path,
+            request_id = tracing::field::Empty,
+        )
+    )]
     pub async fn learn_from_code(&self, request: &LearnRequest) -> Result<(), RAGProxyError> {
-        let url = format!("{}/insert_code", self.config.lightrag.url);
-        
         let request_body = serde_json::json!({
             "file_path": request.file_path,
             "code": request.code,
@@ -172,30 +579,27 @@ impl RAGService {
             "context": request.context
         });
 
-        let response = self.client
-            .post(&url)
-            .json(&request_body)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(RAGProxyError::ServiceError(
-                format!("LightRAG learning failed: {}", response.status())
-            ))
-        }
+        self.backend.insert_code(request_body).await
     }
 
+    #[tracing::instrument(
+        skip(self, request, spec_kit_context),
+        fields(
+            language = %request.language,
+            file_path = %request.file_path,
+            mode = "hybrid",
+            top_k = 5,
+            spec_kit_context = spec_kit_context.is_some(),
+            request_id = tracing::field::Empty,
+        )
+    )]
     pub async fn explain_code(
         &self,
         request: &CodeContextRequest,
         spec_kit_context: &Option<String>,
     ) -> Result<HashMap<String, String>, RAGProxyError> {
-        let url = format!("{}/query", self.config.lightrag.url);
-        
         let explanation_query = format!("Explain this {} code:\n{}", request.language, request.code);
-        
+
         let mut request_body = serde_json::json!({
             "query": explanation_query,
             "mode": "hybrid",
@@ -208,43 +612,31 @@ impl RAGService {
             request_body["query"] = serde_json::Value::String(enhanced_query);
         }
 
-        let response = self.client
-            .post(&url)
-            .json(&request_body)
-            .send()
-            .await?;
+        let result = self.backend.query(request_body).await?;
 
-        if response.status().is_success() {
-            let result: serde_json::Value = response.json().await?;
-            
-            let mut explanation = HashMap::new();
-            
-            if let Some(rag_result) = result.get("result").and_then(|r| r.as_str()) {
-                explanation.insert("explanation".to_string(), rag_result.to_string());
-                explanation.insert("source".to_string(), "lightrag".to_string());
-                explanation.insert("spec_kit_enriched".to_string(), spec_kit_context.is_some().to_string());
-                
-                // Add methodology-specific insights
-                if let Some(spec_context) = spec_kit_context {
-                    if spec_context.contains("level3") || spec_context.contains("level4") {
-                        explanation.insert("methodology".to_string(), "Complex System Architecture".to_string());
-                    } else if spec_context.contains("level2") {
-                        explanation.insert("methodology".to_string(), "Intermediate Feature Development".to_string());
-                    } else {
-                        explanation.insert("methodology".to_string(), "Quick Bug Fix / Simple Enhancement".to_string());
-                    }
+        let mut explanation = HashMap::new();
+
+        if let Some(rag_result) = result.get("result").and_then(|r| r.as_str()) {
+            explanation.insert("explanation".to_string(), rag_result.to_string());
+            explanation.insert("source".to_string(), "lightrag".to_string());
+            explanation.insert("spec_kit_enriched".to_string(), spec_kit_context.is_some().to_string());
+
+            // Add methodology-specific insights
+            if let Some(spec_context) = spec_kit_context {
+                if spec_context.contains("level3") || spec_context.contains("level4") {
+                    explanation.insert("methodology".to_string(), "Complex System Architecture".to_string());
+                } else if spec_context.contains("level2") {
+                    explanation.insert("methodology".to_string(), "Intermediate Feature Development".to_string());
+                } else {
+                    explanation.insert("methodology".to_string(), "Quick Bug Fix / Simple Enhancement".to_string());
                 }
-            } else {
-                explanation.insert("explanation".to_string(), "No specific explanation available from RAG system.".to_string());
-                explanation.insert("source".to_string(), "fallback".to_string());
             }
-            
-            Ok(explanation)
         } else {
-            Err(RAGProxyError::ServiceError(
-                format!("LightRAG explanation failed: {}", response.status())
-            ))
+            explanation.insert("explanation".to_string(), "No specific explanation available from RAG system.".to_string());
+            explanation.insert("source".to_string(), "fallback".to_string());
         }
+
+        Ok(explanation)
     }
 
     async fn enhance_suggestions_with_spec_kit(
@@ -262,15 +654,15 @@ impl RAGService {
                 source: Some("spec_kit".to_string()),
                 // Additional fields would go here if needed
             };
-            
+
             suggestions.push(methodology_suggestion);
-            
+
             // Enhance existing suggestions with Spec Kit context
             for _suggestion in &mut suggestions {
                 // Enhance suggestions with Spec Kit context if needed
             }
         }
-        
+
         suggestions
     }
 
@@ -330,3 +722,51 @@ impl RAGService {
         ]
     }
 }
+
+/// Wraps a chunked HTTP response body in an SSE line parser: splits the byte stream on
+/// `data:` frames (terminated by a blank line, per the eventsource framing), accumulating
+/// partial chunks across reqwest poll boundaries and re-emitting one deserialized `T` per
+/// complete frame. A frame whose payload is the literal `[DONE]` ends the stream. Shared by
+/// `suggest_code_stream` (`T = CodeSuggestion`) and `explain_code_stream` (`T = ExplanationFragment`).
+fn sse_frame_stream<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> impl futures::Stream<Item = Result<T, RAGProxyError>> {
+    use futures::StreamExt;
+
+    let mut buffer = String::new();
+    response.bytes_stream().filter_map(move |chunk| {
+        let result = match chunk {
+            Ok(bytes) => {
+                buffer.push_str(&String::from_utf8_lossy(&bytes));
+                let mut out = Vec::new();
+
+                while let Some(frame_end) = buffer.find("\n\n") {
+                    let frame = buffer[..frame_end].to_string();
+                    buffer.drain(..frame_end + 2);
+
+                    for line in frame.lines() {
+                        let Some(payload) = line.strip_prefix("data:") else {
+                            continue;
+                        };
+                        let payload = payload.trim();
+                        if payload == "[DONE]" {
+                            continue;
+                        }
+                        match serde_json::from_str::<T>(payload) {
+                            Ok(item) => out.push(Ok(item)),
+                            Err(e) => out.push(Err(RAGProxyError::ParseError(format!(
+                                "malformed SSE frame from LightRAG: {}",
+                                e
+                            )))),
+                        }
+                    }
+                }
+
+                out
+            }
+            Err(e) => vec![Err(RAGProxyError::HttpError(e))],
+        };
+
+        async move { Some(futures::stream::iter(result)) }
+    }).flatten()
+}
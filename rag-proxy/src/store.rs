@@ -0,0 +1,169 @@
+use crate::error::RAGProxyError;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// Storage backend for `MemoryBankClient`, abstracting over where Memory Bank files actually
+/// live so the proxy can run as multiple stateless replicas sharing one logical Memory Bank
+/// instead of each instance owning a private local directory. Paths are relative (e.g.
+/// `"tasks.md"`, `"creative/foo.md"`); each implementation resolves them against its own root.
+#[async_trait]
+pub trait MemoryBankStore: Send + Sync {
+    async fn read(&self, path: &str) -> Result<String, RAGProxyError>;
+    async fn write(&self, path: &str, content: &str) -> Result<(), RAGProxyError>;
+    async fn exists(&self, path: &str) -> Result<bool, RAGProxyError>;
+    async fn create_dir(&self, path: &str) -> Result<(), RAGProxyError>;
+    /// Lists entries directly under `path` (not recursive), relative to `path`.
+    async fn list(&self, path: &str) -> Result<Vec<String>, RAGProxyError>;
+}
+
+/// The original local-filesystem backend, rooted at `memory_bank_path`.
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+#[async_trait]
+impl MemoryBankStore for FsStore {
+    async fn read(&self, path: &str) -> Result<String, RAGProxyError> {
+        Ok(fs::read_to_string(self.root.join(path)).await?)
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<(), RAGProxyError> {
+        Ok(fs::write(self.root.join(path), content).await?)
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, RAGProxyError> {
+        Ok(self.root.join(path).exists())
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), RAGProxyError> {
+        Ok(fs::create_dir_all(self.root.join(path)).await?)
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<String>, RAGProxyError> {
+        let dir = self.root.join(path);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut entries = fs::read_dir(&dir).await?;
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(name) = entry.file_name().to_str() {
+                names.push(name.to_string());
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// S3-compatible object-store backend, so several stateless proxy replicas can share one
+/// Memory Bank. Configured via `MEMORY_BANK_S3_BUCKET` / `MEMORY_BANK_S3_PREFIX`; credentials
+/// and region come from the standard AWS environment/profile chain.
+pub struct S3Store {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3Store {
+    pub async fn from_env(bucket: String, prefix: String) -> Self {
+        let sdk_config = aws_config::load_from_env().await;
+        Self {
+            client: aws_sdk_s3::Client::new(&sdk_config),
+            bucket,
+            prefix,
+        }
+    }
+
+    fn key(&self, path: &str) -> String {
+        format!("{}/{}", self.prefix.trim_end_matches('/'), path)
+    }
+}
+
+#[async_trait]
+impl MemoryBankStore for S3Store {
+    async fn read(&self, path: &str) -> Result<String, RAGProxyError> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+            .map_err(|e| RAGProxyError::service_error(format!("S3 get_object failed: {e}")))?;
+        let bytes = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| RAGProxyError::service_error(format!("S3 body read failed: {e}")))?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| RAGProxyError::service_error(format!("S3 object is not valid UTF-8: {e}")))
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<(), RAGProxyError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .body(content.to_owned().into_bytes().into())
+            .send()
+            .await
+            .map_err(|e| RAGProxyError::service_error(format!("S3 put_object failed: {e}")))?;
+        Ok(())
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, RAGProxyError> {
+        match self.client.head_object().bucket(&self.bucket).key(self.key(path)).send().await {
+            Ok(_) => Ok(true),
+            Err(aws_sdk_s3::error::SdkError::ServiceError(e)) if e.err().is_not_found() => Ok(false),
+            Err(e) => Err(RAGProxyError::service_error(format!("S3 head_object failed: {e}"))),
+        }
+    }
+
+    async fn create_dir(&self, _path: &str) -> Result<(), RAGProxyError> {
+        // S3 has no real directories; keys under a prefix come into existence as objects are
+        // written, so there's nothing to create up front.
+        Ok(())
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<String>, RAGProxyError> {
+        let prefix = format!("{}/", self.key(path));
+        let output = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(&prefix)
+            // Without a delimiter, S3 returns every key recursively beneath `prefix`, which
+            // would surface nested files as if they were direct children (and collide basenames
+            // across subdirectories). The delimiter splits the result into direct-child objects
+            // (`contents()`) and direct-child "directories" (`common_prefixes()`), matching
+            // `FsStore::list`'s non-recursive contract.
+            .delimiter("/")
+            .send()
+            .await
+            .map_err(|e| RAGProxyError::service_error(format!("S3 list_objects_v2 failed: {e}")))?;
+
+        let files = output
+            .contents()
+            .iter()
+            .filter_map(|obj| obj.key())
+            .filter_map(|key| key.strip_prefix(prefix.as_str()))
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string());
+
+        let dirs = output
+            .common_prefixes()
+            .iter()
+            .filter_map(|common| common.prefix())
+            .filter_map(|name| name.strip_prefix(prefix.as_str()))
+            .map(|name| name.trim_end_matches('/').to_string());
+
+        Ok(files.chain(dirs).collect())
+    }
+}
@@ -57,6 +57,53 @@ pub struct SuggestionResponse {
     pub memory_bank_context: Option<String>,
     pub cached: bool,
     pub processing_time_ms: u64,
+    /// TTL (seconds) a fresh entry for this key is stored with, from `CacheConfig`/`RAGConfig`.
+    pub cache_ttl_seconds: u64,
+    /// Age of the served entry, `None` when `cached` is false or caching is disabled.
+    pub cache_age_ms: Option<u64>,
+}
+
+/// What actually gets stored as a cache value: the serialized payload plus the wall-clock
+/// time it was written, so `cache_age_ms` can be computed regardless of which `CacheBackend`
+/// is in use (an `Instant` doesn't survive a Redis/Supabase round trip).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEnvelope {
+    pub payload: String,
+    pub cached_at_ms: u64,
+}
+
+impl CacheEnvelope {
+    pub fn wrap(payload: String) -> Self {
+        Self {
+            payload,
+            cached_at_ms: current_unix_ms(),
+        }
+    }
+
+    pub fn age_ms(&self) -> u64 {
+        current_unix_ms().saturating_sub(self.cached_at_ms)
+    }
+}
+
+fn current_unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// Streaming variants emitted over `/api/suggest/stream` as SSE `data:` frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionDelta {
+    pub suggestion: CodeSuggestion,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionStreamDone {
+    pub total: usize,
+    pub cached: bool,
+    pub processing_time_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +113,13 @@ pub struct LearnResponse {
     pub processing_time_ms: u64,
 }
 
+/// Returned by `/api/learn` once the learn job has been enqueued; poll `GET /api/jobs/:id`
+/// for completion instead of waiting on the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobAccepted {
+    pub job_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExplanationResponse {
     pub explanation: String,
@@ -74,6 +128,49 @@ pub struct ExplanationResponse {
     pub processing_time_ms: u64,
 }
 
+// Streaming variants emitted over `/api/explain/stream` as SSE `data:` frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplanationFragment {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplanationStreamDone {
+    pub processing_time_ms: u64,
+}
+
+/// One entry in a `POST /api/batch` request body, tagged by `op` so the array can mix
+/// suggest/search/explain items freely.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchOperation {
+    Suggest(CodeContextRequest),
+    Search(SearchRequest),
+    Explain(CodeContextRequest),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+/// Per-item outcome in a `/api/batch` response; one failing item carries its own error
+/// instead of failing the whole batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    pub op: String,
+    pub success: bool,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub processing_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResponse {
+    pub results: Vec<BatchItemResult>,
+    pub batch_processing_time_ms: u64,
+}
+
 // Health check types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceStatus {
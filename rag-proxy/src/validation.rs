@@ -0,0 +1,53 @@
+use crate::config::LimitsConfig;
+use crate::error::RAGProxyError;
+
+/// Checked at the top of `/api/suggest`, `/api/learn`, and `/api/explain` before any cache
+/// lookup or RAG call, so oversized or unsupported-language requests are rejected cheaply.
+pub fn validate_code_request(
+    limits: &LimitsConfig,
+    file_path: &str,
+    code: &str,
+    language: &str,
+) -> Result<(), RAGProxyError> {
+    if file_path.len() > limits.max_file_path_length {
+        return Err(RAGProxyError::validation_error(format!(
+            "file_path length {} exceeds max_file_path_length {}",
+            file_path.len(),
+            limits.max_file_path_length
+        )));
+    }
+
+    if code.len() > limits.max_code_bytes {
+        return Err(RAGProxyError::payload_too_large(format!(
+            "code size {} bytes exceeds max_code_bytes {}",
+            code.len(),
+            limits.max_code_bytes
+        )));
+    }
+
+    if !limits.allowed_languages.is_empty()
+        && !limits.allowed_languages.iter().any(|allowed| allowed.eq_ignore_ascii_case(language))
+    {
+        return Err(RAGProxyError::validation_error(format!(
+            "language '{language}' is not in the configured allow-list"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checked at the top of `/api/search` (and `/api/batch`'s `search` operation) before any cache
+/// lookup or RAG call. Search requests have no `file_path`/`language` to check, but an
+/// unbounded `query` string is the same overload vector `max_code_bytes` already guards against
+/// for `validate_code_request`, so it's reused here rather than adding a dedicated limit.
+pub fn validate_search_request(limits: &LimitsConfig, query: &str) -> Result<(), RAGProxyError> {
+    if query.len() > limits.max_code_bytes {
+        return Err(RAGProxyError::payload_too_large(format!(
+            "query size {} bytes exceeds max_code_bytes {}",
+            query.len(),
+            limits.max_code_bytes
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use crate::cache_backend::CacheBackend;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Notify, RwLock};
+
+/// A named background task driven on its own interval by `WorkerManager`. A `tick()` should do
+/// one unit of periodic work and return `Err` on failure rather than panicking where avoidable,
+/// though a panic is still caught and recorded as `WorkerState::Dead` instead of silently
+/// killing the task the way a bare `tokio::spawn` loop would.
+#[async_trait]
+pub trait Worker: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn tick(&self) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", content = "reason", rename_all = "lowercase")]
+pub enum WorkerState {
+    Idle,
+    Busy,
+    Dead(String),
+}
+
+/// Snapshot of one worker's run state, returned by `GET /api/workers`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub name: String,
+    pub state: WorkerState,
+    pub paused: bool,
+    pub interval_ms: u64,
+    pub last_run_unix_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+/// Command accepted by `POST /api/workers/:name`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum WorkerCommand {
+    Pause,
+    Resume,
+    Trigger,
+    SetInterval { interval_ms: u64 },
+}
+
+struct WorkerHandle {
+    worker: Arc<dyn Worker>,
+    state: RwLock<WorkerState>,
+    paused: AtomicBool,
+    interval_ms: AtomicU64,
+    last_run_unix_ms: AtomicU64,
+    last_error: RwLock<Option<String>>,
+    trigger: Notify,
+}
+
+/// Supervises named background workers: each is driven on its own configurable interval (or
+/// an immediate manual trigger), panics are caught instead of silently killing the task, and
+/// `/api/workers` exposes every worker's state, last-run timestamp, and last error so background
+/// activity is introspectable and restartable rather than opaque.
+#[derive(Default)]
+pub struct WorkerManager {
+    workers: DashMap<String, Arc<WorkerHandle>>,
+}
+
+impl WorkerManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `worker` and immediately spawns its supervised tick loop on `interval`.
+    pub fn register(&self, worker: Arc<dyn Worker>, interval: Duration) {
+        let name = worker.name().to_string();
+        let handle = Arc::new(WorkerHandle {
+            worker,
+            state: RwLock::new(WorkerState::Idle),
+            paused: AtomicBool::new(false),
+            interval_ms: AtomicU64::new(interval.as_millis().max(1) as u64),
+            last_run_unix_ms: AtomicU64::new(0),
+            last_error: RwLock::new(None),
+            trigger: Notify::new(),
+        });
+        self.workers.insert(name, handle.clone());
+        tokio::spawn(Self::run(handle));
+    }
+
+    async fn run(handle: Arc<WorkerHandle>) {
+        loop {
+            let interval_ms = handle.interval_ms.load(Ordering::Relaxed).max(1);
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_millis(interval_ms)) => {}
+                _ = handle.trigger.notified() => {}
+            }
+
+            if handle.paused.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            *handle.state.write().await = WorkerState::Busy;
+            let outcome = match AssertUnwindSafe(handle.worker.tick()).catch_unwind().await {
+                Ok(result) => result,
+                Err(panic) => Err(panic_message(panic)),
+            };
+
+            handle.last_run_unix_ms.store(now_unix_ms(), Ordering::Relaxed);
+            match outcome {
+                Ok(()) => {
+                    *handle.state.write().await = WorkerState::Idle;
+                    *handle.last_error.write().await = None;
+                }
+                Err(e) => {
+                    *handle.state.write().await = WorkerState::Dead(e.clone());
+                    *handle.last_error.write().await = Some(e);
+                }
+            }
+        }
+    }
+
+    pub async fn status(&self) -> Vec<WorkerStatus> {
+        let mut statuses = Vec::new();
+        for entry in self.workers.iter() {
+            let handle = entry.value();
+            let last_run = handle.last_run_unix_ms.load(Ordering::Relaxed);
+            statuses.push(WorkerStatus {
+                name: entry.key().clone(),
+                state: handle.state.read().await.clone(),
+                paused: handle.paused.load(Ordering::Relaxed),
+                interval_ms: handle.interval_ms.load(Ordering::Relaxed),
+                last_run_unix_ms: if last_run == 0 { None } else { Some(last_run) },
+                last_error: handle.last_error.read().await.clone(),
+            });
+        }
+        statuses
+    }
+
+    pub fn pause(&self, name: &str) -> bool {
+        self.with_handle(name, |h| h.paused.store(true, Ordering::Relaxed))
+    }
+
+    pub fn resume(&self, name: &str) -> bool {
+        self.with_handle(name, |h| h.paused.store(false, Ordering::Relaxed))
+    }
+
+    pub fn trigger(&self, name: &str) -> bool {
+        self.with_handle(name, |h| h.trigger.notify_one())
+    }
+
+    pub fn set_interval(&self, name: &str, interval_ms: u64) -> bool {
+        self.with_handle(name, |h| h.interval_ms.store(interval_ms.max(1), Ordering::Relaxed))
+    }
+
+    fn with_handle(&self, name: &str, f: impl FnOnce(&WorkerHandle)) -> bool {
+        match self.workers.get(name) {
+            Some(handle) => {
+                f(&handle);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn panic_message(panic: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "worker panicked".to_string()
+    }
+}
+
+/// Periodically sweeps expired entries out of the active `CacheBackend`. Replaces the
+/// unmonitored `tokio::spawn` loop that used to live inside `CacheManager` itself.
+pub struct CacheCleanupWorker {
+    cache: Arc<dyn CacheBackend>,
+}
+
+impl CacheCleanupWorker {
+    pub fn new(cache: Arc<dyn CacheBackend>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl Worker for CacheCleanupWorker {
+    fn name(&self) -> &'static str {
+        "cache_cleanup"
+    }
+
+    async fn tick(&self) -> Result<(), String> {
+        self.cache.cleanup_expired().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use dashmap::DashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Sharded, interior-mutable replacement for `Arc<lru::LruCache<_>>`: `lru::LruCache::get`/
+/// `put` require `&mut self`, which doesn't compose with a cache shared read-only across
+/// concurrent requests through `RAGState`. Backed by `DashMap` so `get`/`put` only need `&self`,
+/// with a per-entry TTL and a generation counter so Memory Bank writers can invalidate every
+/// cached suggestion without walking the map.
+pub struct TtlCache {
+    entries: DashMap<String, (String, Instant)>,
+    ttl: Duration,
+    max_size: usize,
+    generation: AtomicU64,
+}
+
+impl TtlCache {
+    pub fn new(ttl: Duration, max_size: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            max_size,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Bumps the generation counter so every key built before this call becomes unreachable:
+    /// new lookups are scoped to the new generation, so stale entries are simply never hit
+    /// again and age out via their own TTL instead of needing an eager full scan.
+    pub fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Builds the cache key for `(file_path, code hash, current_mode)`, scoped to the current
+    /// generation so a `bump_generation` call invalidates every previously-built key.
+    pub fn key(&self, file_path: &str, code: &str, current_mode: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        code.hash(&mut hasher);
+        format!(
+            "{}:{}:{:x}:{}",
+            self.generation.load(Ordering::Relaxed),
+            file_path,
+            hasher.finish(),
+            current_mode
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let expired = match self.entries.get(key) {
+            Some(entry) => entry.value().1.elapsed() > self.ttl,
+            None => return None,
+        };
+        if expired {
+            self.entries.remove(key);
+            return None;
+        }
+        self.entries.get(key).map(|entry| entry.value().0.clone())
+    }
+
+    pub fn put(&self, key: String, value: String) {
+        if self.entries.len() >= self.max_size {
+            self.evict_oldest();
+        }
+        self.entries.insert(key, (value, Instant::now()));
+    }
+
+    fn evict_oldest(&self) {
+        let oldest_key = self
+            .entries
+            .iter()
+            .min_by_key(|entry| entry.value().1)
+            .map(|entry| entry.key().clone());
+        if let Some(key) = oldest_key {
+            self.entries.remove(&key);
+        }
+    }
+
+    /// Manual full bust for `POST /api/cache/invalidate`.
+    pub fn invalidate_all(&self) {
+        self.entries.clear();
+    }
+}
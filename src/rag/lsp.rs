@@ -0,0 +1,147 @@
+//! Exposes the same suggestion engine `create_rag_router` serves over HTTP as a Language
+//! Server Protocol server, so editors get Spec-Kit-aware completions without a custom client.
+
+use std::collections::HashMap;
+
+use dashmap::DashMap;
+use tower_lsp::jsonrpc::Result as LspResult;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{Client, LanguageServer, LspService, Server};
+
+use super::rag_proxy::{suggest, CodeContext, RAGState};
+
+/// Tracks open documents and forwards completion requests into [`suggest`], the same
+/// function `create_rag_router`'s `/api/suggest` handler calls.
+pub struct LspBackend {
+    client: Client,
+    state: RAGState,
+    documents: DashMap<Url, String>,
+    workspace_root: DashMap<(), String>,
+}
+
+impl LspBackend {
+    pub fn new(client: Client, state: RAGState) -> Self {
+        Self {
+            client,
+            state,
+            documents: DashMap::new(),
+            workspace_root: DashMap::new(),
+        }
+    }
+
+    fn workspace_context(&self) -> Option<String> {
+        self.workspace_root.get(&()).map(|root| root.clone())
+    }
+
+    fn language_id_for(&self, uri: &Url) -> String {
+        uri.to_file_path()
+            .ok()
+            .and_then(|path| path.extension().map(|ext| ext.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "plaintext".to_string())
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for LspBackend {
+    async fn initialize(&self, params: InitializeParams) -> LspResult<InitializeResult> {
+        if let Some(root) = params
+            .root_uri
+            .and_then(|uri| uri.to_file_path().ok())
+            .and_then(|path| path.to_str().map(str::to_string))
+        {
+            self.workspace_root.insert((), root);
+        }
+
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                completion_provider: Some(CompletionOptions::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client
+            .log_message(MessageType::INFO, "vibecode-spec-kit RAG LSP ready")
+            .await;
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.documents
+            .insert(params.text_document.uri, params.text_document.text);
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // We only advertise `TextDocumentSyncKind::FULL`, so there's exactly one edit and it
+        // is the document's new full text.
+        if let Some(change) = params.content_changes.pop() {
+            self.documents.insert(params.text_document.uri, change.text);
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.documents.remove(&params.text_document.uri);
+    }
+
+    async fn completion(&self, params: CompletionParams) -> LspResult<Option<CompletionResponse>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let Some(code) = self.documents.get(&uri).map(|doc| doc.clone()) else {
+            return Ok(None);
+        };
+
+        let mut cursor_position = HashMap::new();
+        cursor_position.insert("line".to_string(), position.line as i32);
+        cursor_position.insert("character".to_string(), position.character as i32);
+
+        let context = CodeContext {
+            file_path: uri.to_string(),
+            code,
+            language: self.language_id_for(&uri),
+            cursor_position: Some(cursor_position),
+            project_context: self.workspace_context(),
+        };
+
+        let response = suggest(&self.state, context).await;
+
+        let items = response
+            .suggestions
+            .into_iter()
+            .enumerate()
+            .map(|(index, suggestion)| CompletionItem {
+                label: suggestion.text.clone(),
+                kind: Some(CompletionItemKind::TEXT),
+                detail: suggestion.spec_kit_integration.clone(),
+                documentation: suggestion
+                    .spec_kit_integration
+                    .map(Documentation::String),
+                insert_text: Some(suggestion.text),
+                // Higher-confidence suggestions should sort first; zero-padding keeps the
+                // lexicographic `sort_text` ordering aligned with descending confidence.
+                sort_text: Some(format!("{:05}", (10000.0 - suggestion.confidence * 10000.0) as u32 + index as u32)),
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
+    async fn shutdown(&self) -> LspResult<()> {
+        Ok(())
+    }
+}
+
+/// Serves the LSP subsystem over stdio. Run alongside (or instead of) `create_rag_router`,
+/// e.g. as a second binary target invoked by editors that launch language servers directly.
+pub async fn run_stdio(state: RAGState) {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+
+    let (service, socket) = LspService::new(|client| LspBackend::new(client, state));
+    Server::new(stdin, stdout, socket).serve(service).await;
+}
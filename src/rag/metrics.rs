@@ -0,0 +1,118 @@
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Latency bucket upper bounds in milliseconds for the `/metrics` histogram.
+const BUCKETS_MS: [u64; 7] = [10, 50, 100, 250, 500, 1000, 5000];
+
+#[derive(Default)]
+struct Counter(AtomicU64);
+
+impl Counter {
+    fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [AtomicU64; BUCKETS_MS.len()],
+    sum_ms: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, elapsed_ms: u64) {
+        for (bound, bucket) in BUCKETS_MS.iter().zip(self.buckets.iter()) {
+            if elapsed_ms <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.sum_ms.fetch_add(elapsed_ms, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str) {
+        for (bound, bucket) in BUCKETS_MS.iter().zip(self.buckets.iter()) {
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{route=\"suggest\",le=\"{bound}\"}} {}",
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{route=\"suggest\",le=\"+Inf\"}} {}",
+            self.count.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(out, "{name}_sum{{route=\"suggest\"}} {}", self.sum_ms.load(Ordering::Relaxed));
+        let _ = writeln!(out, "{name}_count{{route=\"suggest\"}} {}", self.count.load(Ordering::Relaxed));
+    }
+}
+
+/// Request counters, a latency histogram, and upstream health gauges for `create_rag_router`,
+/// served as Prometheus text exposition at `/metrics`.
+#[derive(Default)]
+pub struct Metrics {
+    suggest_requests: Counter,
+    suggest_latency: Histogram,
+    cache_hits: Counter,
+    cache_misses: Counter,
+    lightrag_failures: Counter,
+    lightrag_up: AtomicU64,
+    memory_bank_up: AtomicU64,
+}
+
+impl Metrics {
+    pub fn record_suggest(&self, elapsed_ms: u64, cached: bool) {
+        self.suggest_requests.inc();
+        self.suggest_latency.observe(elapsed_ms);
+        if cached {
+            self.cache_hits.inc();
+        } else {
+            self.cache_misses.inc();
+        }
+    }
+
+    pub fn record_lightrag_failure(&self) {
+        self.lightrag_failures.inc();
+    }
+
+    pub fn set_upstream_status(&self, lightrag_healthy: bool, memory_bank_healthy: bool) {
+        self.lightrag_up.store(lightrag_healthy as u64, Ordering::Relaxed);
+        self.memory_bank_up.store(memory_bank_healthy as u64, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP rag_suggest_requests_total Suggestion requests handled.");
+        let _ = writeln!(out, "# TYPE rag_suggest_requests_total counter");
+        let _ = writeln!(out, "rag_suggest_requests_total {}", self.suggest_requests.get());
+
+        let _ = writeln!(out, "# HELP rag_suggest_duration_ms Suggestion handler latency in milliseconds.");
+        let _ = writeln!(out, "# TYPE rag_suggest_duration_ms histogram");
+        self.suggest_latency.render(&mut out, "rag_suggest_duration_ms");
+
+        let _ = writeln!(out, "# HELP rag_cache_hits_total Suggestion cache hits.");
+        let _ = writeln!(out, "# TYPE rag_cache_hits_total counter");
+        let _ = writeln!(out, "rag_cache_hits_total {}", self.cache_hits.get());
+        let _ = writeln!(out, "# HELP rag_cache_misses_total Suggestion cache misses.");
+        let _ = writeln!(out, "# TYPE rag_cache_misses_total counter");
+        let _ = writeln!(out, "rag_cache_misses_total {}", self.cache_misses.get());
+
+        let _ = writeln!(out, "# HELP rag_lightrag_failures_total Failed calls to LightRAG from get_suggestions_from_lightrag.");
+        let _ = writeln!(out, "# TYPE rag_lightrag_failures_total counter");
+        let _ = writeln!(out, "rag_lightrag_failures_total {}", self.lightrag_failures.get());
+
+        let _ = writeln!(out, "# HELP rag_upstream_up Per-service health as reported by health_check.");
+        let _ = writeln!(out, "# TYPE rag_upstream_up gauge");
+        let _ = writeln!(out, "rag_upstream_up{{service=\"lightrag\"}} {}", self.lightrag_up.load(Ordering::Relaxed));
+        let _ = writeln!(out, "rag_upstream_up{{service=\"memory_bank\"}} {}", self.memory_bank_up.load(Ordering::Relaxed));
+
+        out
+    }
+}
@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod lsp;
+pub mod metrics;
+pub mod rag_proxy;
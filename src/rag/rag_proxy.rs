@@ -12,13 +12,16 @@ use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
 use crate::core::memory_bank::MemoryBank;
+use super::cache::TtlCache;
+use super::metrics::Metrics;
 
 #[derive(Clone)]
 pub struct RAGState {
     pub memory_bank: Arc<MemoryBank>,
     pub lightrag_url: String,
     pub n8n_url: String,
-    pub cache: Arc<lru::LruCache<String, String>>,
+    pub cache: Arc<TtlCache>,
+    pub metrics: Arc<Metrics>,
 }
 
 #[derive(Deserialize)]
@@ -38,7 +41,7 @@ pub struct SuggestionResponse {
     pub cached: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct CodeSuggestion {
     pub text: String,
     pub confidence: f64,
@@ -55,9 +58,19 @@ impl RAGState {
                 .unwrap_or_else(|_| "http://localhost:8000".to_string()),
             n8n_url: std::env::var("N8N_URL")
                 .unwrap_or_else(|_| "http://localhost:5678".to_string()),
-            cache: Arc::new(lru::LruCache::new(
-                std::num::NonZeroUsize::new(1000).unwrap(),
+            cache: Arc::new(TtlCache::new(
+                std::time::Duration::from_secs(
+                    std::env::var("RAG_CACHE_TTL_SECONDS")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(300),
+                ),
+                std::env::var("RAG_CACHE_MAX_SIZE")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1000),
             )),
+            metrics: Arc::new(Metrics::default()),
         }
     }
 
@@ -88,10 +101,12 @@ pub fn create_rag_router(state: RAGState) -> Router {
 
     Router::new()
         .route("/health", get(health_check))
+        .route("/metrics", get(metrics_handler))
         .route("/api/suggest", post(suggest_code))
         .route("/api/context/search", post(search_context))
         .route("/api/learn", post(learn_from_code))
         .route("/api/spec-kit/integrate", post(integrate_with_spec_kit))
+        .route("/api/cache/invalidate", post(invalidate_cache))
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(state)
@@ -99,48 +114,72 @@ pub fn create_rag_router(state: RAGState) -> Router {
 
 async fn health_check(State(state): State<RAGState>) -> Json<HashMap<String, String>> {
     let mut services = HashMap::new();
-    
+
     // Проверяем Memory Bank
-    match state.memory_bank.health_check().await {
-        Ok(_) => services.insert("memory_bank".to_string(), "healthy".to_string()),
-        Err(_) => services.insert("memory_bank".to_string(), "unhealthy".to_string()),
-    };
-    
+    let memory_bank_healthy = state.memory_bank.health_check().await.is_ok();
+    services.insert(
+        "memory_bank".to_string(),
+        if memory_bank_healthy { "healthy".to_string() } else { "unhealthy".to_string() },
+    );
+
     // Проверяем LightRAG
-    match check_lightrag(&state.lightrag_url).await {
-        Ok(_) => services.insert("lightrag".to_string(), "healthy".to_string()),
-        Err(_) => services.insert("lightrag".to_string(), "unhealthy".to_string()),
-    };
+    let lightrag_healthy = check_lightrag(&state.lightrag_url).await.is_ok();
+    services.insert(
+        "lightrag".to_string(),
+        if lightrag_healthy { "healthy".to_string() } else { "unhealthy".to_string() },
+    );
+
+    state.metrics.set_upstream_status(lightrag_healthy, memory_bank_healthy);
 
     services.insert("status".to_string(), "healthy".to_string());
     Json(services)
 }
 
+/// Prometheus text-exposition endpoint for `create_rag_router`: suggestion request counts and
+/// latency, cache hit/miss counters, LightRAG failure counts, and per-service health gauges.
+async fn metrics_handler(State(state): State<RAGState>) -> String {
+    state.metrics.render()
+}
+
 async fn suggest_code(
     State(state): State<RAGState>,
     Json(context): Json<CodeContext>,
 ) -> Result<Json<SuggestionResponse>, StatusCode> {
-    let cache_key = format!("suggest:{}:{}", context.file_path, context.code.len());
-    
+    Ok(Json(suggest(&state, context).await))
+}
+
+/// Core completion logic shared by the HTTP handler and the LSP subsystem
+/// (`lsp::LspBackend::completion`), so both surfaces stay behind one implementation.
+pub async fn suggest(state: &RAGState, context: CodeContext) -> SuggestionResponse {
+    let start_time = std::time::Instant::now();
+    let current_mode = state
+        .memory_bank
+        .get_current_mode()
+        .await
+        .unwrap_or_else(|_| "unknown".to_string());
+    let cache_key = state.cache.key(&context.file_path, &context.code, &current_mode);
+
     // Проверяем кеш
     if let Some(cached_response) = state.cache.get(&cache_key) {
-        let cached_suggestions: Vec<CodeSuggestion> = 
-            serde_json::from_str(cached_response).unwrap_or_default();
-        
-        return Ok(Json(SuggestionResponse {
+        let cached_suggestions: Vec<CodeSuggestion> =
+            serde_json::from_str(&cached_response).unwrap_or_default();
+
+        state.metrics.record_suggest(start_time.elapsed().as_millis() as u64, true);
+        return SuggestionResponse {
             suggestions: cached_suggestions,
             context: context.code.clone(),
             memory_bank_context: None,
             cached: true,
-        }));
+        };
     }
 
     // Получаем контекст Spec Kit
     let spec_kit_context = state.get_spec_kit_context(&context).await;
 
     // Получаем предложения от LightRAG с контекстом Spec Kit
-    let mut suggestions = get_suggestions_from_lightrag(&state, &context, &spec_kit_context).await
+    let mut suggestions = get_suggestions_from_lightrag(state, &context, &spec_kit_context).await
         .unwrap_or_else(|| {
+            state.metrics.record_lightrag_failure();
             vec![CodeSuggestion {
                 text: "// AI suggestion with Spec Kit context".to_string(),
                 confidence: 0.7,
@@ -151,7 +190,7 @@ async fn suggest_code(
         });
 
     // Добавляем Spec Kit специфичные предложения
-    if let Some(spec_context) = spec_kit_context {
+    if spec_kit_context.is_some() {
         suggestions.push(CodeSuggestion {
             text: "// Spec Kit methodology suggestion".to_string(),
             confidence: 0.8,
@@ -166,12 +205,22 @@ async fn suggest_code(
         state.cache.put(cache_key, cached_json);
     }
 
-    Ok(Json(SuggestionResponse {
+    state.metrics.record_suggest(start_time.elapsed().as_millis() as u64, false);
+    SuggestionResponse {
         suggestions,
         context: context.code,
         memory_bank_context: spec_kit_context,
         cached: false,
-    }))
+    }
+}
+
+/// Manual cache bust for operators, distinct from `bump_generation`: this clears every entry
+/// outright instead of just scoping new keys to a fresh generation.
+async fn invalidate_cache(State(state): State<RAGState>) -> Json<HashMap<String, String>> {
+    state.cache.invalidate_all();
+    let mut response = HashMap::new();
+    response.insert("status".to_string(), "invalidated".to_string());
+    Json(response)
 }
 
 async fn integrate_with_spec_kit(
@@ -189,6 +238,10 @@ async fn integrate_with_spec_kit(
     // Интегрируем с Memory Bank
     match state.memory_bank.integrate_rag_context(spec_type, code).await {
         Ok(result) => {
+            // Spec Kit integration can change the active mode/context that suggestions are
+            // keyed on, so previously cached suggestions are no longer trustworthy.
+            state.cache.bump_generation();
+
             let mut response = HashMap::new();
             response.insert("status".to_string(), "success".to_string());
             response.insert("integration".to_string(), result);
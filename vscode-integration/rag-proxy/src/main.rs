@@ -5,7 +5,10 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{collections::HashMap, sync::Arc};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
@@ -18,6 +21,67 @@ struct AppState {
     n8n_user: String,
     n8n_password: String,
     cache: Arc<lru::LruCache<String, String>>,
+    /// When set, outbound n8n POSTs are signed with this key instead of (in addition to)
+    /// basic auth, so a verifying reverse proxy can reject anything not actually from us.
+    signing: Option<Arc<OutboundSigner>>,
+}
+
+/// Signs outbound requests with an ed25519 HTTP Signature (draft-cavage style): a
+/// `Signature` header covering `(request-target)`, `host`, `date`, and `digest`.
+struct OutboundSigner {
+    key_id: String,
+    signing_key: SigningKey,
+}
+
+impl OutboundSigner {
+    /// Loads the signing key from `SIGNING_KEY` (base64-encoded 32-byte ed25519 seed).
+    /// Returns `None` (rather than an error) when `SIGN_OUTBOUND` isn't enabled, so
+    /// existing basic-auth-only deployments are unaffected.
+    fn from_env() -> Option<Self> {
+        if std::env::var("SIGN_OUTBOUND").map(|v| v == "true").unwrap_or(false) {
+            let key_b64 = std::env::var("SIGNING_KEY").expect(
+                "SIGN_OUTBOUND=true requires SIGNING_KEY (base64-encoded ed25519 seed)",
+            );
+            let key_bytes = base64::engine::general_purpose::STANDARD
+                .decode(key_b64.trim())
+                .expect("SIGNING_KEY must be valid base64");
+            let seed: [u8; 32] = key_bytes
+                .try_into()
+                .expect("SIGNING_KEY must decode to exactly 32 bytes");
+
+            Some(Self {
+                key_id: std::env::var("SIGNING_KEY_ID").unwrap_or_else(|_| "rag-proxy".to_string()),
+                signing_key: SigningKey::from_bytes(&seed),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Builds the `Signature`, `Digest`, and `Date` header values for a POST to `host` with
+    /// the given JSON `body`. The canonical signing string covers, in order, the
+    /// `(request-target)` pseudo-header, `host`, `date`, and `digest`.
+    fn sign_post(&self, path: &str, host: &str, body: &[u8]) -> (String, String, String) {
+        let date = httpdate::fmt_http_date(std::time::SystemTime::now());
+        let digest = format!(
+            "SHA-256={}",
+            base64::engine::general_purpose::STANDARD.encode(Sha256::digest(body))
+        );
+
+        let signing_string = format!(
+            "(request-target): post {}\nhost: {}\ndate: {}\ndigest: {}",
+            path, host, date, digest
+        );
+        let signature_bytes = self.signing_key.sign(signing_string.as_bytes());
+        let signature_b64 = base64::engine::general_purpose::STANDARD.encode(signature_bytes.to_bytes());
+
+        let signature_header = format!(
+            "keyId=\"{}\",algorithm=\"ed25519\",headers=\"(request-target) host date digest\",signature=\"{}\"",
+            self.key_id, signature_b64
+        );
+
+        (signature_header, digest, date)
+    }
 }
 
 #[derive(Deserialize)]
@@ -65,6 +129,7 @@ async fn main() {
         cache: Arc::new(lru::LruCache::new(
             std::num::NonZeroUsize::new(1000).unwrap(),
         )),
+        signing: OutboundSigner::from_env().map(Arc::new),
     };
 
     let cors = CorsLayer::new()
@@ -226,11 +291,33 @@ async fn trigger_workflow(
     Json(payload): Json<HashMap<String, serde_json::Value>>,
 ) -> Result<Json<HashMap<String, serde_json::Value>>, StatusCode> {
     let client = reqwest::Client::new();
-    
-    let response = client
-        .post(&format!("{}/webhook/{}", state.n8n_url, workflow_id))
-        .basic_auth(&state.n8n_user, Some(&state.n8n_password))
-        .json(&payload)
+
+    let path = format!("/webhook/{}", workflow_id);
+    let host = state
+        .n8n_url
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .to_string();
+    let body = serde_json::to_vec(&payload).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut request = client
+        .post(&format!("{}{}", state.n8n_url, path))
+        .basic_auth(&state.n8n_user, Some(&state.n8n_password));
+
+    // Additionally sign the request so a verifying reverse proxy in front of n8n can reject
+    // anything that didn't actually come from this proxy, independent of the basic-auth creds.
+    if let Some(signer) = &state.signing {
+        let (signature, digest, date) = signer.sign_post(&path, &host, &body);
+        request = request
+            .header("Host", host)
+            .header("Date", date)
+            .header("Digest", digest)
+            .header("Signature", signature);
+    }
+
+    let response = request
+        .body(body)
+        .header("Content-Type", "application/json")
         .send()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;